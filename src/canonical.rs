@@ -0,0 +1,249 @@
+use crate::transform::Transform;
+use crate::{Cell, Grid};
+
+/// The 6 orderings of 3 elements, used for bands, stacks and the rows
+/// or columns within them.
+const PERMS_3: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+type Values = [[u8; 9]; 9];
+
+/// Branch and bound search for the smallest grid string, with a fixed
+/// transpose and column order. Rows are picked one at a time, and digits
+/// are relabelled in order of first appearance, which is always the best
+/// relabelling for a given layout.
+struct RowSearch<'a> {
+    values: &'a Values,
+    cols: [usize; 9],
+    best: &'a mut Best,
+    transpose: bool,
+}
+
+struct Best {
+    string: [u8; 81],
+    transpose: bool,
+    rows: [usize; 9],
+    cols: [usize; 9],
+    labels: [u8; 10],
+}
+
+impl<'a> RowSearch<'a> {
+    fn search(
+        &mut self,
+        depth: usize,
+        rows: &mut [usize; 9],
+        used: &mut [bool; 9],
+        string: &mut [u8; 81],
+        labels: [u8; 10],
+        next_label: u8,
+    ) {
+        if depth == 9 {
+            if string[..] < self.best.string[..] {
+                self.best.string = *string;
+                self.best.transpose = self.transpose;
+                self.best.rows = *rows;
+                self.best.cols = self.cols;
+                self.best.labels = labels;
+            }
+            return;
+        }
+        let choices: Vec<usize> = if depth % 3 == 0 {
+            (0..9).filter(|r| !used[*r]).collect()
+        } else {
+            let band = rows[depth - 1] / 3;
+            (band * 3..band * 3 + 3).filter(|r| !used[*r]).collect()
+        };
+        for row in choices {
+            let mut labels = labels;
+            let mut next_label = next_label;
+            for (j, col) in self.cols.iter().enumerate() {
+                let v = self.values[row][*col];
+                if v != 0 && labels[v as usize] == 0 {
+                    labels[v as usize] = next_label;
+                    next_label += 1;
+                }
+                string[depth * 9 + j] = labels[v as usize];
+            }
+            let end = (depth + 1) * 9;
+            if string[..end] > self.best.string[..end] {
+                continue;
+            }
+            rows[depth] = row;
+            used[row] = true;
+            self.search(depth + 1, rows, used, string, labels, next_label);
+            used[row] = false;
+        }
+    }
+}
+
+fn column_orders() -> Vec<[usize; 9]> {
+    let mut orders = vec![];
+    for stacks in PERMS_3.iter() {
+        for a in PERMS_3.iter() {
+            for b in PERMS_3.iter() {
+                for c in PERMS_3.iter() {
+                    let within = [a, b, c];
+                    let mut order = [0; 9];
+                    for (i, stack) in stacks.iter().enumerate() {
+                        for (j, col) in within[i].iter().enumerate() {
+                            order[i * 3 + j] = stack * 3 + col;
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// Transform mapping a grid to its canonical form.
+pub fn canonical_transform(grid: &Grid) -> Transform {
+    let mut best = Best {
+        string: [u8::MAX; 81],
+        transpose: false,
+        rows: [0; 9],
+        cols: [0; 9],
+        labels: [0; 10],
+    };
+    let orders = column_orders();
+    for transpose in [false, true].iter() {
+        let mut values: Values = [[0; 9]; 9];
+        for (i, cell) in grid.cells.iter().enumerate() {
+            let (r, c) = if *transpose {
+                (i % 9, i / 9)
+            } else {
+                (i / 9, i % 9)
+            };
+            if let Cell::Solved(v) = cell {
+                values[r][c] = (*v).into();
+            }
+        }
+        for cols in orders.iter() {
+            let mut search = RowSearch {
+                values: &values,
+                cols: *cols,
+                best: &mut best,
+                transpose: *transpose,
+            };
+            search.search(0, &mut [0; 9], &mut [false; 9], &mut [0; 81], [0; 10], 1);
+        }
+    }
+    // The search picks a source row and column for each position,
+    // a Transform maps each source to its destination.
+    let mut rows = [0; 9];
+    let mut cols = [0; 9];
+    for i in 0..9 {
+        rows[best.rows[i]] = i as u8;
+        cols[best.cols[i]] = i as u8;
+    }
+    // Digits missing from the grid take the remaining labels in order
+    let mut next_label = best.labels.iter().max().unwrap() + 1;
+    let mut digits = [0; 9];
+    for (d, digit) in digits.iter_mut().enumerate() {
+        let label = &mut best.labels[d + 1];
+        if *label == 0 {
+            *label = next_label;
+            next_label += 1;
+        }
+        *digit = *label - 1;
+    }
+    Transform::from_permutations(best.transpose, rows, cols, digits)
+}
+
+/// The lexicographically smallest grid, reading solved cells as digits and
+/// unsolved cells as 0, among all grids equivalent under transposition, row
+/// and column permutations within bands and stacks, band and stack
+/// permutations and digit relabelling. Two puzzles are equivalent if and
+/// only if they have the same canonical form.
+pub fn canonical_form(grid: &Grid) -> Grid {
+    canonical_transform(grid).apply(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::fixtures::PUZZLE;
+
+    #[test]
+    fn test_canonical_form_is_invariant() {
+        let grid = Grid::from_str(PUZZLE).unwrap();
+        let canonical = canonical_form(&grid);
+        let t = Transform::rotate_clockwise()
+            .then(&Transform::swap_bands(1, 3).unwrap())
+            .then(&Transform::swap_cols(4, 5).unwrap())
+            .then(&Transform::relabel([9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap());
+        assert_eq!(canonical_form(&t.apply(&grid)), canonical);
+        assert_eq!(canonical_form(&canonical), canonical);
+    }
+
+    #[test]
+    fn test_canonical_form_is_minimal() {
+        let grid = Grid::from_str(PUZZLE).unwrap();
+        let canonical = canonical_form(&grid).to_string();
+        for t in [
+            Transform::identity(),
+            Transform::transpose(),
+            Transform::rotate_180(),
+            Transform::reflect_vertical(),
+        ]
+        .iter()
+        {
+            assert!(canonical <= t.apply(&grid).to_string());
+        }
+    }
+
+    /// Band, stack, row and column permutations, maybe a transpose, and a
+    /// relabelling, all picked at random.
+    fn random_transform(rng: &mut StdRng) -> Transform {
+        let mut lines = || {
+            let outer = PERMS_3.choose(rng).unwrap();
+            let mut perm = [0; 9];
+            for (i, &block) in outer.iter().enumerate() {
+                let inner = PERMS_3.choose(rng).unwrap();
+                for (j, &line) in inner.iter().enumerate() {
+                    perm[i * 3 + j] = (block * 3 + line) as u8;
+                }
+            }
+            perm
+        };
+        let rows = lines();
+        let cols = lines();
+        let mut digits = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        digits.shuffle(rng);
+        Transform::from_permutations(rng.gen(), rows, cols, digits)
+    }
+
+    #[test]
+    fn test_random_copies_share_canonical_form() {
+        let grid = Grid::from_str(PUZZLE).unwrap();
+        let canonical = canonical_form(&grid).to_string();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let copy = random_transform(&mut rng).apply(&grid);
+            assert!(canonical <= copy.to_string());
+            assert_eq!(canonical_form(&copy).to_string(), canonical);
+        }
+    }
+
+    #[test]
+    fn test_different_puzzles_differ() {
+        let a = Grid::from_str(PUZZLE).unwrap();
+        let mut s = PUZZLE.to_string();
+        s.replace_range(0..1, "4");
+        let b = Grid::from_str(&s).unwrap();
+        assert_ne!(canonical_form(&a), canonical_form(&b));
+    }
+}
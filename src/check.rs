@@ -0,0 +1,141 @@
+//! Checking a player's progress on a puzzle against its solution.
+
+use crate::solver::backtrack::{has_unique_solution, unique_solution};
+use crate::solver::strategies::all_strategies;
+use crate::solver::Solver;
+use crate::{Cell, CellValue, Error, Grid, Position, Result};
+
+/// A cell solved with the wrong value.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WrongValue {
+    pub position: Position,
+    pub value: CellValue,
+    pub expected: CellValue,
+}
+
+/// A cell whose candidates no longer include its solution.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WrongElimination {
+    pub position: Position,
+    pub expected: CellValue,
+}
+
+/// Mistakes found in a partially solved grid, in row order.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mistakes {
+    pub wrong_values: Vec<WrongValue>,
+    pub wrong_eliminations: Vec<WrongElimination>,
+}
+
+impl Mistakes {
+    pub fn is_empty(&self) -> bool {
+        self.wrong_values.is_empty() && self.wrong_eliminations.is_empty()
+    }
+}
+
+/// The unique solution of a puzzle. The logical strategies are tried first,
+/// since they only make sound deductions a full solve proves uniqueness,
+/// and the brute-force search is used when they stall. A full solve is
+/// still checked for conflicts, as conflicting givens are never removed.
+pub fn solution(puzzle: &Grid) -> Result<Grid> {
+    let mut grid = *puzzle;
+    Solver::new(all_strategies()).solve(&mut grid);
+    if grid.is_solved() && has_unique_solution(&grid) {
+        return Ok(grid);
+    }
+    unique_solution(puzzle).ok_or_else(|| Error::new("Puzzle does not have a unique solution"))
+}
+
+/// Compare a grid with the solution of the puzzle it was started from.
+pub fn check_progress(puzzle: &Grid, progress: &Grid) -> Result<Mistakes> {
+    Ok(compare(&solution(puzzle)?, progress))
+}
+
+/// Compare a grid with a known solution.
+pub fn compare(solution: &Grid, progress: &Grid) -> Mistakes {
+    let mut mistakes = Mistakes::default();
+    for position in Position::row_vecs().into_iter().flatten() {
+        let expected = match solution.get_cell(position) {
+            Cell::Solved(v) => v,
+            Cell::Unsolved(_) => continue,
+        };
+        match progress.get_cell(position) {
+            Cell::Solved(value) if value != expected => mistakes.wrong_values.push(WrongValue {
+                position,
+                value,
+                expected,
+            }),
+            Cell::Unsolved(candidates) if !candidates.can_be(&expected) => mistakes
+                .wrong_eliminations
+                .push(WrongElimination { position, expected }),
+            _ => {}
+        }
+    }
+    mistakes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::{PUZZLE, SOLUTION};
+
+    #[test]
+    fn test_correct_progress() {
+        let puzzle = Grid::from_str(PUZZLE).unwrap();
+        let mut progress = puzzle;
+        progress.set_cell(Position::new(1, 1), Cell::Solved(4.into()));
+        let solution = Grid::from_str(SOLUTION).unwrap();
+        assert!(compare(&solution, &progress).is_empty());
+    }
+
+    #[test]
+    fn test_wrong_value_and_elimination() {
+        let puzzle = Grid::from_str(PUZZLE).unwrap();
+        let mut progress = puzzle;
+        progress.set_cell(Position::new(2, 1), Cell::Solved(5.into()));
+        let mut cell = progress.get_cell(Position::new(3, 1));
+        if let Cell::Unsolved(candidates) = &mut cell {
+            candidates.remove(&6.into());
+        }
+        progress.set_cell(Position::new(3, 1), cell);
+        let mistakes = check_progress(&puzzle, &progress).unwrap();
+        assert_eq!(
+            mistakes.wrong_values,
+            vec![WrongValue {
+                position: Position::new(2, 1),
+                value: 5.into(),
+                expected: 3.into(),
+            }]
+        );
+        assert_eq!(
+            mistakes.wrong_eliminations,
+            vec![WrongElimination {
+                position: Position::new(3, 1),
+                expected: 6.into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_solution() {
+        let puzzle = Grid::from_str(PUZZLE).unwrap();
+        assert_eq!(solution(&puzzle).unwrap().to_string(), SOLUTION);
+        assert!(solution(&Grid::new()).is_err());
+    }
+
+    #[test]
+    fn test_conflicting_givens() {
+        // Two 4s in row 1, in a grid that is otherwise full
+        let full = SOLUTION.replacen('3', "4", 1);
+        assert!(solution(&Grid::from_str(&full).unwrap()).is_err());
+        // Two 9s in box 1, left for the strategies to fill around
+        let puzzle = PUZZLE.replacen("000120000", "009120000", 1);
+        let puzzle = Grid::from_str(&puzzle).unwrap();
+        assert!(solution(&puzzle).is_err());
+    }
+}
@@ -1,46 +1,23 @@
-use std::convert::TryInto;
 use std::fmt;
 use std::iter::FromIterator;
-use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use structopt::StructOpt;
 
+use sudoku::puzzle::{parse_puzzles, Puzzle};
 use sudoku::solver::strategies::all_strategies;
 use sudoku::solver::{SolutionStep, Solver};
 use sudoku::stats::{Count, Formatted, Maximum, Mean, Minimum, Report, ReportBuilder};
 use sudoku::threads::ThreadMode;
-use sudoku::{Error, Grid};
+use sudoku::Error;
 
-const PUZZLE_STR: &str = include_str!("../puzzles/benchmark.txt");
+use super::read_puzzle_file;
 
-struct Puzzle {
-    grid: Grid,
-    rating: f64,
-}
-
-impl FromStr for Puzzle {
-    type Err = Error;
-    fn from_str(s: &str) -> std::result::Result<Self, <Self as FromStr>::Err> {
-        let [_, puzzle_str, rating]: [&str; 3] = s
-            .split(' ')
-            .collect::<Vec<&str>>()
-            .try_into()
-            .expect("puzzle should be str like '<id> <puzzle> <rating>'");
-        Ok(Puzzle {
-            grid: Grid::from_str(puzzle_str)?,
-            rating: rating.parse().unwrap(),
-        })
-    }
-}
-
-fn parse_puzzles(s: &str) -> Vec<Puzzle> {
-    s.trim()
-        .split('\n')
-        .map(|s| Puzzle::from_str(s).unwrap())
-        .collect()
-}
+const PUZZLE_STR: &str = include_str!("../../puzzles/benchmark.txt");
 
 struct BenchmarkReport {
     report: Report<f64>,
@@ -158,40 +135,81 @@ fn round_robin_split<T>(vec: Vec<T>, n: usize) -> Vec<Vec<T>> {
     vv
 }
 
+/// Solve the benchmark puzzles and report statistics
 #[derive(Debug, StructOpt)]
-struct Cli {
+pub struct BenchCmd {
     #[structopt(long, short, default_value)]
     threads: ThreadMode,
+    /// Read '<id> <puzzle> <rating>' puzzles from a file, or from stdin with
+    /// "-", instead of the built-in set. Can be repeated
+    #[structopt(long, short, number_of_values = 1)]
+    input: Vec<String>,
+    /// Only solve this many puzzles, the first ones or a random sample
+    /// with --shuffle
+    #[structopt(long, short)]
+    limit: Option<usize>,
+    /// Solve the puzzles in a random order
+    #[structopt(long)]
+    shuffle: bool,
+    /// Seed for --shuffle, to sample the same puzzles again
+    #[structopt(long, requires = "shuffle")]
+    seed: Option<u64>,
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let puzzles = parse_puzzles(PUZZLE_STR);
-
-    eprintln!("starting benchmark with {} threads...", args.threads);
-    match args.threads {
-        ThreadMode::SingleThreaded => {
-            let mut measurements = vec![];
-            measurements.extend(run_benchmark(puzzles));
-            let benchmark: BenchmarkReport = measurements.iter().collect();
-            println!("{}", benchmark);
+impl BenchCmd {
+    fn puzzles(&self) -> Result<Vec<Puzzle>, Error> {
+        let mut puzzles = vec![];
+        if self.input.is_empty() {
+            puzzles = parse_puzzles(PUZZLE_STR)?;
         }
-        ThreadMode::MultiThreaded(threads) => {
-            let measurements = Arc::new(Mutex::new(vec![]));
-            let puzzle_groups = round_robin_split(puzzles, threads);
-            let mut handles = vec![];
-            for puzzles in puzzle_groups.into_iter() {
-                let measurements = measurements.clone();
-                handles.push(thread::spawn(move || {
-                    let res = run_benchmark(puzzles);
-                    measurements.lock().unwrap().extend(res);
-                }));
+        for input in &self.input {
+            puzzles.extend(read_puzzle_file(input)?);
+        }
+        if self.shuffle {
+            let mut rng = match self.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            puzzles.shuffle(&mut rng);
+        }
+        if let Some(limit) = self.limit {
+            puzzles.truncate(limit);
+        }
+        Ok(puzzles)
+    }
+
+    pub fn run(&self) -> Result<bool, Error> {
+        let puzzles = self.puzzles()?;
+        if puzzles.is_empty() {
+            return Err(Error::new("no puzzles to benchmark"));
+        }
+
+        eprintln!("starting benchmark with {} threads...", self.threads);
+        match self.threads {
+            ThreadMode::SingleThreaded => {
+                let mut measurements = vec![];
+                measurements.extend(run_benchmark(puzzles));
+                let benchmark: BenchmarkReport = measurements.iter().collect();
+                println!("{}", benchmark);
             }
-            for h in handles {
-                h.join().unwrap();
+            ThreadMode::MultiThreaded(threads) => {
+                let measurements = Arc::new(Mutex::new(vec![]));
+                let puzzle_groups = round_robin_split(puzzles, threads);
+                let mut handles = vec![];
+                for puzzles in puzzle_groups.into_iter() {
+                    let measurements = measurements.clone();
+                    handles.push(thread::spawn(move || {
+                        let res = run_benchmark(puzzles);
+                        measurements.lock().unwrap().extend(res);
+                    }));
+                }
+                for h in handles {
+                    h.join().unwrap();
+                }
+                let benchmark: BenchmarkReport = measurements.lock().unwrap().iter().collect();
+                println!("{}", benchmark);
             }
-            let benchmark: BenchmarkReport = measurements.lock().unwrap().iter().collect();
-            println!("{}", benchmark);
         }
+        Ok(true)
     }
 }
@@ -0,0 +1,59 @@
+use structopt::StructOpt;
+
+use sudoku::generator::Generator;
+use sudoku::latex::Booklet;
+use sudoku::{Error, Grid};
+
+use super::read_puzzles;
+
+/// Lay out puzzles as a printable booklet, printing LaTeX source
+#[derive(Debug, StructOpt)]
+pub struct BookletCmd {
+    /// Read puzzles from a file, one per line, or from stdin with "-".
+    /// Puzzles are generated if omitted.
+    #[structopt(long, short)]
+    input: Option<String>,
+    /// Number of puzzles to generate when there is no input
+    #[structopt(long, short = "n", default_value = "8")]
+    count: usize,
+    /// Seed for reproducible puzzles
+    #[structopt(long, conflicts_with = "input")]
+    seed: Option<u64>,
+    /// Puzzles on each page, from 1 to 12
+    #[structopt(long, short, default_value = "4")]
+    per_page: usize,
+    /// Print the solutions on the last pages
+    #[structopt(long, short)]
+    solutions: bool,
+    /// Title on the first page
+    #[structopt(long, short)]
+    title: Option<String>,
+}
+
+impl BookletCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        let puzzles: Vec<Grid> = match &self.input {
+            Some(input) => read_puzzles(input)?
+                .into_iter()
+                .map(|(_, grid)| grid)
+                .collect(),
+            None => {
+                let mut generator = Generator::new();
+                if let Some(seed) = self.seed {
+                    generator = generator.with_seed(seed);
+                }
+                (0..self.count)
+                    .map(|_| generator.generate())
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        let mut booklet = Booklet::new(&puzzles)
+            .with_per_page(self.per_page)
+            .with_solutions(self.solutions);
+        if let Some(title) = &self.title {
+            booklet = booklet.with_title(title);
+        }
+        print!("{}", booklet.render()?);
+        Ok(true)
+    }
+}
@@ -0,0 +1,60 @@
+use structopt::StructOpt;
+
+use sudoku::solver::backtrack::count_solutions;
+use sudoku::Error;
+
+use super::{run, OutputOpts, PuzzleOpts, Report};
+
+/// Count the solutions of puzzles
+#[derive(Debug, StructOpt)]
+pub struct CountCmd {
+    /// Stop counting after this many solutions
+    #[structopt(long, short, default_value = "1000")]
+    limit: usize,
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct SolutionCount {
+    solutions: usize,
+    limit_reached: bool,
+}
+
+impl Report for SolutionCount {
+    fn text(&self) -> String {
+        if self.limit_reached {
+            format!("{}+", self.solutions)
+        } else {
+            self.solutions.to_string()
+        }
+    }
+
+    fn status(&self) -> String {
+        match (self.solutions, self.limit_reached) {
+            (0, _) => "without solution",
+            (1, false) => "with a unique solution",
+            (_, false) => "with several solutions",
+            (_, true) => "at the limit",
+        }
+        .to_string()
+    }
+}
+
+impl CountCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        if self.limit == 0 {
+            return Err(Error::new("--limit must be at least 1"));
+        }
+        run(&self.puzzles, &self.output, |grid| {
+            let solutions = count_solutions(grid, self.limit);
+            SolutionCount {
+                solutions,
+                limit_reached: solutions == self.limit,
+            }
+        })
+    }
+}
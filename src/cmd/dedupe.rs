@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use structopt::StructOpt;
+
+use sudoku::canonical::canonical_form;
+use sudoku::puzzle::Puzzle;
+use sudoku::Error;
+
+use super::read_puzzle_file;
+
+/// Puzzles that are transformations of one another, in input order.
+struct Class {
+    canonical: String,
+    puzzles: Vec<Puzzle>,
+}
+
+fn group_puzzles(puzzles: Vec<Puzzle>) -> Vec<Class> {
+    let mut classes: Vec<Class> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for puzzle in puzzles {
+        let canonical = canonical_form(&puzzle.grid).to_string();
+        match index.get(&canonical) {
+            Some(i) => classes[*i].puzzles.push(puzzle),
+            None => {
+                index.insert(canonical.clone(), classes.len());
+                classes.push(Class {
+                    canonical,
+                    puzzles: vec![puzzle],
+                });
+            }
+        }
+    }
+    classes
+}
+
+/// Find puzzles which are transformations of one another
+#[derive(Debug, StructOpt)]
+pub struct DedupeCmd {
+    /// Print each group of equivalent puzzles as '<canonical> <id>...'
+    /// instead of the first puzzle of each group
+    #[structopt(long, short)]
+    groups: bool,
+    /// Puzzle files, one '<id> <puzzle> <rating>' per line, or stdin with "-"
+    #[structopt(required = true)]
+    files: Vec<String>,
+}
+
+impl DedupeCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        let mut puzzles = vec![];
+        for file in &self.files {
+            puzzles.extend(read_puzzle_file(file)?);
+        }
+        let count = puzzles.len();
+        let classes = group_puzzles(puzzles);
+        for class in &classes {
+            if self.groups {
+                let ids: Vec<&str> = class.puzzles.iter().map(|p| p.id.as_str()).collect();
+                println!("{} {}", class.canonical, ids.join(" "));
+            } else {
+                let first = &class.puzzles[0];
+                println!("{} {} {}", first.id, first.grid, first.rating);
+            }
+        }
+        eprintln!("{} puzzles, {} distinct", count, classes.len());
+        Ok(true)
+    }
+}
@@ -0,0 +1,68 @@
+use structopt::StructOpt;
+
+use sudoku::generator::{ClueMask, Generator, Symmetry};
+use sudoku::rating::{rate, Rating};
+use sudoku::solver::{strategies::all_strategies, Solver};
+use sudoku::Error;
+
+use super::{print_report, OutputOpts, Report};
+
+/// Generate puzzles with a unique solution
+#[derive(Debug, StructOpt)]
+pub struct GenerateCmd {
+    /// Number of puzzles to generate
+    #[structopt(long, short = "n", default_value = "1")]
+    count: usize,
+    /// Seed for reproducible puzzles
+    #[structopt(long)]
+    seed: Option<u64>,
+    /// Clue symmetry: none, rotational, diagonal, horizontal or vertical
+    #[structopt(long, short, default_value = "none")]
+    symmetry: Symmetry,
+    /// Cells allowed to hold clues, 81 characters where 'x' allows a clue and '.' forbids it
+    #[structopt(long, short)]
+    mask: Option<ClueMask>,
+    #[structopt(flatten)]
+    output: OutputOpts,
+}
+
+/// A generated puzzle, printed like the lines of the puzzle files so that
+/// the output can be read back with --input.
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct Generated {
+    puzzle: String,
+    rating: Rating,
+}
+
+impl Report for Generated {
+    fn text(&self) -> String {
+        format!("{} {:.1}", self.puzzle, self.rating.score)
+    }
+
+    fn status(&self) -> String {
+        "generated".to_string()
+    }
+}
+
+impl GenerateCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        let mut generator = Generator::new().with_symmetry(self.symmetry);
+        if let Some(seed) = self.seed {
+            generator = generator.with_seed(seed);
+        }
+        if let Some(mask) = self.mask {
+            generator = generator.with_mask(mask);
+        }
+        let solver = Solver::new(all_strategies());
+        for i in 0..self.count {
+            let grid = generator.generate()?;
+            let generated = Generated {
+                puzzle: grid.to_string(),
+                rating: rate(&grid, Some(&solver)),
+            };
+            print_report(&generated, Some(&(i + 1).to_string()), &self.output.format);
+        }
+        Ok(true)
+    }
+}
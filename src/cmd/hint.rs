@@ -0,0 +1,43 @@
+use structopt::StructOpt;
+
+use sudoku::hint::{Hint, HintLevel};
+use sudoku::solver::{strategies::all_strategies, Solver};
+use sudoku::Error;
+
+use super::{run, OutputOpts, PuzzleOpts, Report};
+
+/// Show the next step the solver would take, a little at a time
+#[derive(Debug, StructOpt)]
+pub struct HintCmd {
+    /// How much to reveal: technique (1), region (2) or full (3)
+    #[structopt(long, short, default_value = "technique")]
+    level: HintLevel,
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+impl Report for Option<Hint> {
+    fn text(&self) -> String {
+        match self {
+            Some(hint) => hint.to_string(),
+            None => "no strategy applies".to_string(),
+        }
+    }
+
+    fn status(&self) -> String {
+        match self {
+            Some(_) => "with a hint".to_string(),
+            None => "without hint".to_string(),
+        }
+    }
+}
+
+impl HintCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        run(&self.puzzles, &self.output, |grid| {
+            Hint::find(&Solver::new(all_strategies()), grid).map(|h| h.at_level(self.level))
+        })
+    }
+}
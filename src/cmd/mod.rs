@@ -0,0 +1,307 @@
+//! Subcommands of the sudokusolver binary, and the options they share.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
+use std::str::FromStr;
+use std::time::Instant;
+
+use structopt::StructOpt;
+
+use sudoku::parse::parse_state;
+use sudoku::puzzle::{parse_puzzles, Puzzle};
+use sudoku::threads::{map_ordered, ThreadMode};
+use sudoku::{Error, Grid};
+
+pub mod bench;
+pub mod booklet;
+pub mod count;
+pub mod dedupe;
+pub mod generate;
+pub mod hint;
+pub mod rate;
+pub mod render;
+pub mod solve;
+pub mod transform;
+pub mod validate;
+
+#[derive(Debug)]
+pub enum OutputFormat {
+    Text,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "json")]
+    Jsonl,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "text" => Ok(Self::Text),
+            #[cfg(feature = "json")]
+            "json" => Ok(Self::Json),
+            #[cfg(feature = "json")]
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(Error::new(&format!("Unknown output format: {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct OutputOpts {
+    /// Output format: text, json or jsonl (one compact JSON object per line)
+    #[structopt(long, short, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct PuzzleOpts {
+    /// Read puzzles from a file, one per line, or from stdin with "-"
+    #[structopt(long, short, conflicts_with = "puzzle")]
+    pub input: Option<String>,
+    /// Threads used for puzzles from --input
+    #[structopt(long, short, default_value)]
+    pub threads: ThreadMode,
+    /// The puzzle, as 81 digits or a multi-line grid, or a solving state
+    /// as a pencilmark grid or candidate string
+    #[structopt(required_unless = "input")]
+    pub puzzle: Option<String>,
+}
+
+impl PuzzleOpts {
+    /// The puzzle argument, when not reading from --input.
+    pub fn grid(&self) -> Result<Option<Grid>, Error> {
+        match &self.puzzle {
+            Some(s) => parse_state(s)
+                .map(Some)
+                .map_err(|e| Error::new(&format!("invalid puzzle: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+pub trait Serializable: serde::Serialize {}
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> Serializable for T {}
+#[cfg(not(feature = "json"))]
+pub trait Serializable {}
+#[cfg(not(feature = "json"))]
+impl<T> Serializable for T {}
+
+/// Result of a command for one puzzle.
+pub trait Report: Serializable {
+    /// One line of text, printed after the puzzle id in batch mode.
+    fn text(&self) -> String;
+    /// Counted in the summary of a batch, like "solved" or "stalled".
+    fn status(&self) -> String;
+    /// False makes the command exit with an error code.
+    fn is_success(&self) -> bool {
+        true
+    }
+}
+
+impl OutputFormat {
+    /// Whether batch reports are printed as the items of one JSON array.
+    fn is_json_array(&self) -> bool {
+        #[cfg(feature = "json")]
+        if let Self::Json = self {
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(feature = "json")]
+fn report_json<T: Report>(report: &T, id: Option<&str>) -> serde_json::Value {
+    let mut value = serde_json::to_value(report).unwrap();
+    if let (Some(id), Some(object)) = (id, value.as_object_mut()) {
+        object.insert("id".to_string(), serde_json::Value::from(id));
+    }
+    value
+}
+
+pub fn print_report<T: Report>(report: &T, id: Option<&str>, format: &OutputFormat) {
+    match format {
+        OutputFormat::Text => match id {
+            Some(id) => println!("{} {}", id, report.text()),
+            None => println!("{}", report.text()),
+        },
+        #[cfg(feature = "json")]
+        OutputFormat::Json if id.is_none() => println!(
+            "{}",
+            serde_json::to_string_pretty(&report_json(report, id)).unwrap()
+        ),
+        #[cfg(feature = "json")]
+        OutputFormat::Json | OutputFormat::Jsonl => println!("{}", report_json(report, id)),
+    }
+}
+
+/// A report of a batch in the json format, as an item of the array.
+#[cfg(feature = "json")]
+fn print_array_item<T: Report>(report: &T, id: &str, first: bool) {
+    let separator = if first { "[\n" } else { ",\n" };
+    print!("{}  {}", separator, report_json(report, Some(id)));
+}
+#[cfg(not(feature = "json"))]
+fn print_array_item<T: Report>(_report: &T, _id: &str, _first: bool) {}
+
+/// A line of a batch file is either a bare puzzle, identified by its line
+/// number, or `<id> <puzzle> <rating>` as in the puzzles directory, with a
+/// numeric rating.
+fn parse_batch_line(line_no: usize, line: &str) -> Result<(String, Grid), Error> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() == 3 && tokens[2].parse::<f64>().is_ok() {
+        let puzzle = Puzzle::from_str(line)?;
+        Ok((puzzle.id, puzzle.grid))
+    } else {
+        Ok((line_no.to_string(), parse_state(line)?))
+    }
+}
+
+/// Colours for a terminal, unless NO_COLOR is set.
+pub fn use_color(stream: &impl IsTerminal) -> bool {
+    stream.is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+pub fn open_input(input: &str) -> Result<Box<dyn BufRead + Send>, Error> {
+    if input == "-" {
+        Ok(Box::new(BufReader::new(io::stdin())))
+    } else {
+        let file =
+            File::open(input).map_err(|e| Error::new(&format!("cannot open {}: {}", input, e)))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Non-empty lines of a batch file that are not comments, numbered from 0.
+/// A line that cannot be read, like one that is not UTF-8, is an error and
+/// the last item.
+fn batch_lines(input: &str) -> Result<impl Iterator<Item = (usize, Result<String, Error>)>, Error> {
+    let name = input.to_string();
+    let mut failed = false;
+    Ok(open_input(input)?
+        .lines()
+        .enumerate()
+        .map_while(move |(i, line)| {
+            if failed {
+                return None;
+            }
+            failed = line.is_err();
+            let line = line.map_err(|e| Error::new(&format!("cannot read {}: {}", name, e)));
+            Some((i, line))
+        })
+        .filter(|(_, line)| match line {
+            Ok(line) => !line.trim().is_empty() && !line.starts_with('#'),
+            Err(_) => true,
+        }))
+}
+
+/// Puzzles of a '<id> <puzzle> <rating>' file, or of stdin with "-".
+pub fn read_puzzle_file(input: &str) -> Result<Vec<Puzzle>, Error> {
+    let mut s = String::new();
+    open_input(input)?
+        .read_to_string(&mut s)
+        .map_err(|e| Error::new(&format!("cannot read {}: {}", input, e)))?;
+    let name = if input == "-" { "stdin" } else { input };
+    parse_puzzles(&s).map_err(|e| Error::new(&format!("{}: {}", name, e)))
+}
+
+/// All the puzzles of a batch file, with their ids.
+pub fn read_puzzles(input: &str) -> Result<Vec<(String, Grid)>, Error> {
+    batch_lines(input)?
+        .map(|(i, line)| {
+            line.and_then(|line| parse_batch_line(i + 1, line.trim()))
+                .map_err(|e| Error::new(&format!("input line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Run a command on every puzzle of a file, in parallel, printing reports
+/// in input order and a summary of their statuses on stderr. The json
+/// format prints a single array, jsonl one report per line.
+fn run_batch<T, F>(
+    input: &str,
+    threads: &ThreadMode,
+    format: &OutputFormat,
+    f: F,
+) -> Result<bool, Error>
+where
+    T: Report + Send,
+    F: Fn(&Grid) -> T + Sync,
+{
+    let lines = batch_lines(input)?;
+
+    let start = Instant::now();
+    let mut statuses: Vec<(String, usize)> = vec![];
+    let mut success = true;
+    let mut first = true;
+    map_ordered(
+        threads,
+        lines,
+        |(i, line)| {
+            let line = line.map_err(|e| (format!("input line {}: {}", i + 1, e), "unread"))?;
+            let (id, grid) = parse_batch_line(i + 1, line.trim()).map_err(|e| {
+                (
+                    format!("invalid puzzle: input line {}: {}", i + 1, e),
+                    "invalid",
+                )
+            })?;
+            Ok((id, f(&grid)))
+        },
+        |result: Result<(String, T), (String, &str)>| {
+            let status = match result {
+                Ok((id, report)) => {
+                    if format.is_json_array() {
+                        print_array_item(&report, &id, first);
+                        first = false;
+                    } else {
+                        print_report(&report, Some(&id), format);
+                    }
+                    success &= report.is_success();
+                    report.status()
+                }
+                Err((e, status)) => {
+                    eprintln!("{}", e);
+                    success = false;
+                    status.to_string()
+                }
+            };
+            match statuses.iter_mut().find(|(s, _)| *s == status) {
+                Some((_, n)) => *n += 1,
+                None => statuses.push((status, 1)),
+            }
+        },
+    );
+    if format.is_json_array() {
+        println!("{}", if first { "[]" } else { "\n]" });
+    }
+    let counts: Vec<String> = statuses
+        .iter()
+        .map(|(status, n)| format!("{} {}", n, status))
+        .collect();
+    eprintln!(
+        "{} puzzles: {} in {} ms",
+        statuses.iter().map(|(_, n)| n).sum::<usize>(),
+        counts.join(", "),
+        start.elapsed().as_millis()
+    );
+    Ok(success)
+}
+
+/// Run a command on the puzzle argument, or on every puzzle from --input.
+/// Returns false if any report was not a success.
+pub fn run<T, F>(puzzles: &PuzzleOpts, output: &OutputOpts, f: F) -> Result<bool, Error>
+where
+    T: Report + Send,
+    F: Fn(&Grid) -> T + Sync,
+{
+    match &puzzles.input {
+        Some(input) => run_batch(input, &puzzles.threads, &output.format, f),
+        None => {
+            let report = f(&puzzles.grid()?.unwrap());
+            print_report(&report, None, &output.format);
+            Ok(report.is_success())
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use structopt::StructOpt;
+
+use sudoku::rating::{rate, Rating};
+use sudoku::Error;
+
+use super::{run, OutputOpts, PuzzleOpts, Report};
+
+/// Rate puzzles on the Sudoku Explainer scale
+#[derive(Debug, StructOpt)]
+pub struct RateCmd {
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+impl Report for Rating {
+    fn text(&self) -> String {
+        let hardest = self.hardest.as_deref().unwrap_or("none");
+        if self.solved {
+            format!("{:.1} {}", self.score, hardest)
+        } else {
+            format!(">{:.1} {} (stalled)", self.score, hardest)
+        }
+    }
+
+    fn status(&self) -> String {
+        if self.solved {
+            "rated".to_string()
+        } else {
+            "stalled".to_string()
+        }
+    }
+}
+
+impl RateCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        run(&self.puzzles, &self.output, |grid| rate(grid, None))
+    }
+}
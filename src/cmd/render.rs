@@ -0,0 +1,170 @@
+use std::io;
+use std::str::FromStr;
+
+use structopt::StructOpt;
+
+use sudoku::graph::LinkGraph;
+use sudoku::hint::Region;
+#[cfg(feature = "html")]
+use sudoku::html::{GridRenderer, Templates};
+use sudoku::parse::parse_state;
+use sudoku::solver::strategies::all_strategies;
+use sudoku::solver::Solver;
+use sudoku::{svg, terminal};
+use sudoku::{Cell, CellValue, Error, Grid, Position};
+
+use super::use_color;
+
+/// Print a puzzle in another format
+#[derive(Debug, StructOpt)]
+pub struct RenderCmd {
+    /// Output style: line, grid, pencilmarks, candidates, base64, terminal,
+    /// html, svg or dot, the candidate link graph for Graphviz
+    #[structopt(long, short, visible_alias = "format", default_value = "grid")]
+    style: Style,
+    /// Apply the next step of the solver and highlight what it changed,
+    /// for the terminal, html and svg styles
+    #[structopt(long)]
+    step: bool,
+    /// Only the candidates of this digit in the dot style, can be repeated
+    #[structopt(long, number_of_values = 1)]
+    digit: Vec<u8>,
+    /// Only the candidates in this row, column or box in the dot style,
+    /// like r3, c3 or b3
+    #[structopt(long)]
+    region: Option<Region>,
+    /// Directory of HTML templates replacing the built-in ones
+    #[cfg(feature = "html")]
+    #[structopt(long)]
+    templates: Option<String>,
+    /// The puzzle, as 81 digits or a multi-line grid, or a solving state
+    /// as a pencilmark grid or candidate string
+    puzzle: String,
+}
+
+#[derive(Debug)]
+enum Style {
+    Line,
+    Grid,
+    Pencilmarks,
+    Candidates,
+    Base64,
+    Terminal,
+    #[cfg(feature = "html")]
+    Html,
+    Svg,
+    Dot,
+}
+
+impl FromStr for Style {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "line" => Ok(Self::Line),
+            "grid" => Ok(Self::Grid),
+            "pencilmarks" => Ok(Self::Pencilmarks),
+            "candidates" => Ok(Self::Candidates),
+            "base64" => Ok(Self::Base64),
+            "terminal" => Ok(Self::Terminal),
+            #[cfg(feature = "html")]
+            "html" => Ok(Self::Html),
+            "svg" => Ok(Self::Svg),
+            "dot" => Ok(Self::Dot),
+            _ => Err(Error::new(&format!("Unknown style: {}", s))),
+        }
+    }
+}
+
+/// Boxed grid with '.' for blank cells, in the format accepted as input.
+fn boxed(grid: &Grid) -> String {
+    let border = "+-------+-------+-------+\n";
+    let mut out = border.to_string();
+    for (r, row) in Position::row_vecs().into_iter().enumerate() {
+        let cells: Vec<String> = row
+            .into_iter()
+            .map(|p| match grid.get_cell(p) {
+                Cell::Solved(v) => u8::from(v).to_string(),
+                Cell::Unsolved(_) => ".".to_string(),
+            })
+            .collect();
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            cells[0..3].join(" "),
+            cells[3..6].join(" "),
+            cells[6..9].join(" ")
+        ));
+        if r % 3 == 2 {
+            out.push_str(border);
+        }
+    }
+    out
+}
+
+impl RenderCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        let mut grid =
+            parse_state(&self.puzzle).map_err(|e| Error::new(&format!("invalid puzzle: {}", e)))?;
+        let delta = if self.step {
+            let step = Solver::new(all_strategies())
+                .solve_step(&grid)
+                .ok_or_else(|| Error::new("no strategy applies"))?;
+            step.delta.apply(&mut grid);
+            Some(step.delta)
+        } else {
+            None
+        };
+        match self.style {
+            Style::Line => println!("{}", grid),
+            Style::Grid => print!("{}", boxed(&grid)),
+            Style::Pencilmarks => print!("{}", grid.to_pencilmarks()),
+            Style::Candidates => println!("{}", grid.to_candidate_string()),
+            Style::Base64 => println!("{}", grid.to_base64()),
+            Style::Terminal => {
+                let renderer =
+                    terminal::GridRenderer::new(&grid).with_color(use_color(&io::stdout()));
+                match &delta {
+                    Some(delta) => print!("{}", renderer.with_delta(delta).render()),
+                    None => print!("{}", renderer.render()),
+                }
+            }
+            #[cfg(feature = "html")]
+            Style::Html => {
+                let templates = match &self.templates {
+                    Some(dir) => Templates::from_dir(dir)?,
+                    None => Templates::builtin().clone(),
+                };
+                let renderer = GridRenderer::new(&grid).with_templates(&templates);
+                match &delta {
+                    Some(delta) => println!("{}", renderer.with_delta(delta).render()?),
+                    None => println!("{}", renderer.render()?),
+                }
+            }
+            Style::Svg => {
+                let renderer = svg::GridRenderer::new(&grid);
+                match &delta {
+                    Some(delta) => print!("{}", renderer.with_delta(delta).render()),
+                    None => print!("{}", renderer.render()),
+                }
+            }
+            Style::Dot => {
+                let mut graph = LinkGraph::new(&grid);
+                if !self.digit.is_empty() {
+                    let digits = self
+                        .digit
+                        .iter()
+                        .map(|&n| match n {
+                            1..=9 => Ok(CellValue::new(n)),
+                            _ => Err(Error::new(&format!("invalid digit: {}", n))),
+                        })
+                        .collect::<Result<Vec<CellValue>, Error>>()?;
+                    graph = graph.with_digits(&digits);
+                }
+                if let Some(region) = self.region {
+                    graph = graph.with_region(region);
+                }
+                print!("{}", graph.to_dot());
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,192 @@
+use std::io;
+use std::time::Instant;
+
+use structopt::StructOpt;
+
+#[cfg(feature = "html")]
+use sudoku::html::{SolverRenderer, Templates};
+use sudoku::report::{Checkpoints, ReportFormat, ReportRenderer};
+use sudoku::solver::{strategies::all_strategies, SolveReport, Solver};
+use sudoku::svg::AnimationRenderer;
+use sudoku::terminal;
+use sudoku::{Error, Grid};
+
+use super::{run, use_color, OutputFormat, OutputOpts, PuzzleOpts, Report};
+
+/// Solve puzzles with the human strategies, showing each step
+#[derive(Debug, StructOpt)]
+pub struct SolveCmd {
+    /// Render the solution steps as HTML pages in this directory
+    #[cfg(feature = "html")]
+    #[structopt(long = "output-html", short = "o", conflicts_with_all = &["input", "report"])]
+    html_output_dir: Option<String>,
+    /// Write the solution steps as a single HTML page, or a Markdown or
+    /// text report, to this file
+    #[structopt(long, short = "r", conflicts_with = "input")]
+    report: Option<String>,
+    /// Report format: html, markdown or text, by default from the file
+    /// extension (.md, .txt, else html)
+    #[structopt(long)]
+    report_format: Option<String>,
+    /// Grids shown in Markdown and text reports, besides the start and end:
+    /// every N steps, "strategy" when the strategy changes, or "stall" for none
+    #[structopt(long, default_value = "strategy")]
+    checkpoints: Checkpoints,
+    /// Directory of HTML templates replacing the built-in ones
+    #[cfg(feature = "html")]
+    #[structopt(long)]
+    templates: Option<String>,
+    /// Write the solve as an animated SVG to this file
+    #[structopt(long, short = "a", conflicts_with = "input")]
+    animate: Option<String>,
+    /// Frames each step of the animation lasts
+    #[structopt(long, default_value = "24")]
+    frames_per_step: u32,
+    /// Frames per second of the animation
+    #[structopt(long, default_value = "12")]
+    fps: u32,
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+impl Report for SolveReport {
+    fn text(&self) -> String {
+        format!("{} {} {} steps", self.output, self.status, self.steps.len())
+    }
+
+    fn status(&self) -> String {
+        self.status.to_string()
+    }
+}
+
+fn solve_and_print(solver: Solver, mut sudoku: Grid) {
+    let start = Instant::now();
+    let steps = solver.solve(&mut sudoku);
+    for (i, step) in steps.iter().enumerate() {
+        eprintln!(
+            "{} {} ({} μs) : {} ",
+            i,
+            &step.strategy.name(),
+            step.time.as_micros(),
+            step.delta,
+        );
+    }
+    eprintln!("Total time: {} ms", start.elapsed().as_millis());
+    if !sudoku.is_solved() {
+        // The candidates left are what matters when debugging a stalled solve
+        let renderer = terminal::GridRenderer::new(&sudoku).with_color(use_color(&io::stderr()));
+        let grid = match steps.last() {
+            Some(step) => renderer.with_delta(&step.delta).render(),
+            None => renderer.render(),
+        };
+        eprint!("Stalled with:\n{}", grid);
+    }
+    println!("{}", sudoku);
+}
+
+#[cfg(feature = "html")]
+fn solve_and_render_html(
+    renderer: SolverRenderer,
+    mut sudoku: Grid,
+    out_dir: &str,
+) -> Result<(), Error> {
+    renderer.solve_and_render(&mut sudoku, out_dir)?;
+    println!("{}", sudoku);
+    Ok(())
+}
+
+fn solve_and_write_report(
+    render: impl FnOnce(&mut Grid) -> Result<String, Error>,
+    mut sudoku: Grid,
+    path: &str,
+) -> Result<(), Error> {
+    let report = render(&mut sudoku)?;
+    std::fs::write(path, report)
+        .map_err(|e| Error::new(&format!("cannot write {}: {}", path, e)))?;
+    println!("{}", sudoku);
+    Ok(())
+}
+
+fn solve_and_write_animation(
+    solver: Solver,
+    mut sudoku: Grid,
+    path: &str,
+    frames_per_step: u32,
+    fps: u32,
+) -> Result<(), Error> {
+    let puzzle = sudoku;
+    let steps = solver.solve(&mut sudoku);
+    let animation = AnimationRenderer::new(&puzzle, &steps)
+        .with_frames_per_step(frames_per_step)
+        .with_frame_rate(fps)
+        .render();
+    std::fs::write(path, animation)
+        .map_err(|e| Error::new(&format!("cannot write {}: {}", path, e)))?;
+    println!("{}", sudoku);
+    Ok(())
+}
+
+impl SolveCmd {
+    #[cfg(feature = "html")]
+    fn html_renderer(&self) -> Result<SolverRenderer, Error> {
+        let renderer = SolverRenderer::new(Solver::new(all_strategies()));
+        match &self.templates {
+            Some(dir) => Ok(renderer.with_templates(Templates::from_dir(dir)?)),
+            None => Ok(renderer),
+        }
+    }
+
+    pub fn run(&self) -> Result<bool, Error> {
+        #[cfg(feature = "html")]
+        if let Some(dir) = &self.html_output_dir {
+            solve_and_render_html(self.html_renderer()?, self.puzzles.grid()?.unwrap(), dir)?;
+            return Ok(true);
+        }
+        if let Some(path) = &self.report {
+            let grid = self.puzzles.grid()?.unwrap();
+            let format = match &self.report_format {
+                Some(format) => format.as_str(),
+                None => path.rsplit_once('.').map_or("", |(_, ext)| ext),
+            };
+            match format.parse::<ReportFormat>() {
+                Ok(format) => {
+                    let renderer = ReportRenderer::new(Solver::new(all_strategies()))
+                        .with_format(format)
+                        .with_checkpoints(self.checkpoints);
+                    solve_and_write_report(
+                        |grid| Ok(renderer.solve_and_render_report(grid)),
+                        grid,
+                        path,
+                    )?;
+                }
+                #[cfg(feature = "html")]
+                Err(_) if self.report_format.as_deref().is_none_or(|f| f == "html") => {
+                    let renderer = self.html_renderer()?;
+                    solve_and_write_report(
+                        |grid| renderer.solve_and_render_report(grid),
+                        grid,
+                        path,
+                    )?;
+                }
+                Err(e) => return Err(e),
+            }
+            return Ok(true);
+        }
+        if let Some(path) = &self.animate {
+            let solver = Solver::new(all_strategies());
+            let grid = self.puzzles.grid()?.unwrap();
+            solve_and_write_animation(solver, grid, path, self.frames_per_step, self.fps)?;
+            return Ok(true);
+        }
+        if let (OutputFormat::Text, None) = (&self.output.format, &self.puzzles.input) {
+            let solver = Solver::new(all_strategies());
+            solve_and_print(solver, self.puzzles.grid()?.unwrap());
+            return Ok(true);
+        }
+        run(&self.puzzles, &self.output, |grid| {
+            Solver::new(all_strategies()).solve_report(grid)
+        })
+    }
+}
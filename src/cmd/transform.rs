@@ -0,0 +1,111 @@
+use std::convert::TryInto;
+use std::str::FromStr;
+
+use structopt::StructOpt;
+
+use sudoku::canonical::canonical_form;
+use sudoku::transform::Transform;
+use sudoku::{Error, Grid};
+
+use super::{run, OutputOpts, PuzzleOpts, Report};
+
+/// Apply symmetry transformations to puzzles
+#[derive(Debug, StructOpt)]
+pub struct TransformCmd {
+    /// Transformation, applied in order when repeated: transpose, antitranspose,
+    /// rotate-cw, rotate-ccw, rotate-180, reflect-h, reflect-v, swap-rows:A,B,
+    /// swap-cols:A,B, swap-bands:A,B, swap-stacks:A,B, relabel:DIGITS or canonical
+    #[structopt(long = "apply", short = "a", required = true, number_of_values = 1)]
+    ops: Vec<Op>,
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+#[derive(Debug)]
+enum Op {
+    Transform(Transform),
+    /// Depends on the grid, see canonical_form
+    Canonical,
+}
+
+fn parse_pair(name: &str, args: &str) -> Result<(u8, u8), Error> {
+    let invalid = || Error::new(&format!("{} expects two numbers like {}:1,2", name, name));
+    let (a, b) = args.split_once(',').ok_or_else(invalid)?;
+    Ok((
+        a.trim().parse().map_err(|_| invalid())?,
+        b.trim().parse().map_err(|_| invalid())?,
+    ))
+}
+
+fn parse_digits(args: &str) -> Result<[u8; 9], Error> {
+    let digits: Vec<u8> = args
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| Error::new("relabel expects 9 digits like relabel:912345678"))?;
+    digits
+        .try_into()
+        .map_err(|_| Error::new("relabel expects 9 digits like relabel:912345678"))
+}
+
+impl FromStr for Op {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let (name, args) = s.split_once(':').unwrap_or((s, ""));
+        let t = match name {
+            "transpose" => Transform::transpose(),
+            "antitranspose" => Transform::antitranspose(),
+            "rotate-cw" => Transform::rotate_clockwise(),
+            "rotate-ccw" => Transform::rotate_counterclockwise(),
+            "rotate-180" => Transform::rotate_180(),
+            "reflect-h" => Transform::reflect_horizontal(),
+            "reflect-v" => Transform::reflect_vertical(),
+            "swap-rows" | "swap-cols" | "swap-bands" | "swap-stacks" => {
+                let (a, b) = parse_pair(name, args)?;
+                match name {
+                    "swap-rows" => Transform::swap_rows(a, b)?,
+                    "swap-cols" => Transform::swap_cols(a, b)?,
+                    "swap-bands" => Transform::swap_bands(a, b)?,
+                    _ => Transform::swap_stacks(a, b)?,
+                }
+            }
+            "relabel" => Transform::relabel(parse_digits(args)?)?,
+            "canonical" => return Ok(Self::Canonical),
+            _ => return Err(Error::new(&format!("Unknown transformation: {}", s))),
+        };
+        Ok(Self::Transform(t))
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct Transformed {
+    puzzle: String,
+}
+
+impl Report for Transformed {
+    fn text(&self) -> String {
+        self.puzzle.clone()
+    }
+
+    fn status(&self) -> String {
+        "transformed".to_string()
+    }
+}
+
+impl TransformCmd {
+    fn apply(&self, grid: &Grid) -> Grid {
+        self.ops.iter().fold(*grid, |grid, op| match op {
+            Op::Transform(t) => t.apply(&grid),
+            Op::Canonical => canonical_form(&grid),
+        })
+    }
+
+    pub fn run(&self) -> Result<bool, Error> {
+        run(&self.puzzles, &self.output, |grid| Transformed {
+            puzzle: self.apply(grid).to_string(),
+        })
+    }
+}
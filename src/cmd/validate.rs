@@ -0,0 +1,68 @@
+use structopt::StructOpt;
+
+use sudoku::solver::backtrack::count_solutions;
+use sudoku::{Error, Grid};
+
+use super::{run, OutputOpts, PuzzleOpts, Report};
+
+/// Check that puzzles have exactly one solution
+#[derive(Debug, StructOpt)]
+pub struct ValidateCmd {
+    #[structopt(flatten)]
+    output: OutputOpts,
+    #[structopt(flatten)]
+    puzzles: PuzzleOpts,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+enum Validity {
+    Unique,
+    Multiple,
+    Unsolvable,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct Validation {
+    validity: Validity,
+    clues: usize,
+}
+
+impl Validation {
+    fn new(grid: &Grid) -> Self {
+        let validity = match count_solutions(grid, 2) {
+            0 => Validity::Unsolvable,
+            1 => Validity::Unique,
+            _ => Validity::Multiple,
+        };
+        let clues = grid.to_string().chars().filter(|c| *c != '0').count();
+        Self { validity, clues }
+    }
+}
+
+impl Report for Validation {
+    fn text(&self) -> String {
+        format!("{} ({} clues)", self.status(), self.clues)
+    }
+
+    fn status(&self) -> String {
+        match self.validity {
+            Validity::Unique => "valid",
+            Validity::Multiple => "multiple solutions",
+            Validity::Unsolvable => "no solution",
+        }
+        .to_string()
+    }
+
+    fn is_success(&self) -> bool {
+        matches!(self.validity, Validity::Unique)
+    }
+}
+
+impl ValidateCmd {
+    pub fn run(&self) -> Result<bool, Error> {
+        run(&self.puzzles, &self.output, Validation::new)
+    }
+}
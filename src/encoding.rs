@@ -0,0 +1,218 @@
+//! Compact encodings of a full candidate grid, for logging and replaying
+//! solver states. Both encode the 81x9 candidate bitset, a solved cell
+//! having only its value set, and which cells are solved, so that an
+//! unsolved cell with a single candidate decodes as it was.
+
+use crate::{Candidates, Cell, Error, Grid, Result};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The candidate bits, then one solved bit per cell.
+const BITS: usize = 81 * 9 + 81;
+const BYTES: usize = BITS.div_ceil(8);
+
+fn cell_bits(cell: &Cell) -> [bool; 9] {
+    match cell {
+        Cell::Solved(v) => {
+            let mut bits = [false; 9];
+            bits[usize::from(*v) - 1] = true;
+            bits
+        }
+        Cell::Unsolved(candidates) => candidates.0,
+    }
+}
+
+fn bits_cell(i: usize, bits: [bool; 9], solved: bool) -> Result<Cell> {
+    let candidates = Candidates::new(bits);
+    if !solved {
+        return Ok(Cell::Unsolved(candidates));
+    }
+    match candidates.to_vec()[..] {
+        [v] => Ok(Cell::Solved(v)),
+        _ => Err(Error::new(&format!(
+            "Solved cell {} must have exactly one value",
+            i + 1
+        ))),
+    }
+}
+
+impl Grid {
+    fn to_bits(self) -> Vec<bool> {
+        self.cells
+            .iter()
+            .flat_map(cell_bits)
+            .chain(self.cells.iter().map(|c| matches!(c, Cell::Solved(_))))
+            .collect()
+    }
+
+    fn from_bits(bits: &[bool]) -> Result<Grid> {
+        let mut grid = Grid::new();
+        for i in 0..81 {
+            let mut cell = [false; 9];
+            cell.copy_from_slice(&bits[i * 9..i * 9 + 9]);
+            grid.cells[i] = bits_cell(i, cell, bits[81 * 9 + i])?;
+        }
+        Ok(grid)
+    }
+
+    /// 729 characters, 9 per cell in row order, where position k of a cell
+    /// is the digit k + 1 if it is a candidate (or the value). The other
+    /// positions are '.' in an unsolved cell and '-' in a solved one, so
+    /// "----5----" is a solved 5 and "....5...." a single candidate 5.
+    pub fn to_candidate_string(&self) -> String {
+        let bits = self.to_bits();
+        (0..81 * 9)
+            .map(|i| {
+                if bits[i] {
+                    char::from(b'1' + (i % 9) as u8)
+                } else if bits[81 * 9 + i / 9] {
+                    '-'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    pub fn from_candidate_string(s: &str) -> Result<Grid> {
+        if s.chars().count() != 729 {
+            return Err(Error::new("Candidate string must have 729 characters"));
+        }
+        let mut bits = vec![false; BITS];
+        let mut dashes = [0; 81];
+        for (i, c) in s.chars().enumerate() {
+            let digit = char::from(b'1' + (i % 9) as u8);
+            if c == digit {
+                bits[i] = true;
+            } else if c == '-' {
+                dashes[i / 9] += 1;
+            } else if c != '.' && c != '0' {
+                return Err(Error::new(&format!(
+                    "Invalid character '{}' at {} in candidate string, expected '{}', '.' or '-'",
+                    c,
+                    i + 1,
+                    digit
+                )));
+            }
+        }
+        for (i, &n) in dashes.iter().enumerate() {
+            match n {
+                0 => {}
+                8 => bits[81 * 9 + i] = true,
+                _ => {
+                    return Err(Error::new(&format!(
+                        "Solved cell {} must have one digit and '-' elsewhere",
+                        i + 1
+                    )))
+                }
+            }
+        }
+        Grid::from_bits(&bits)
+    }
+
+    /// Base64 of the 729-bit candidate bitset followed by the 81 solved
+    /// bits, most significant bit first, padded with zero bits to 102 bytes
+    /// (136 characters).
+    pub fn to_base64(&self) -> String {
+        let bits = self.to_bits();
+        let mut bytes = [0u8; BYTES];
+        for (i, b) in bits.iter().enumerate() {
+            if *b {
+                bytes[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    pub fn from_base64(s: &str) -> Result<Grid> {
+        let mut bytes = vec![];
+        let mut n: u32 = 0;
+        let mut count = 0;
+        for c in s.trim_end_matches('=').bytes() {
+            let v = match BASE64.iter().position(|x| *x == c) {
+                Some(v) => v as u32,
+                None => {
+                    return Err(Error::new(&format!(
+                        "Invalid base64 character '{}'",
+                        c as char
+                    )))
+                }
+            };
+            n = n << 6 | v;
+            count += 1;
+            if count == 4 {
+                bytes.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8]);
+                n = 0;
+                count = 0;
+            }
+        }
+        match count {
+            0 => {}
+            2 => bytes.push((n >> 4) as u8),
+            3 => bytes.extend_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]),
+            _ => return Err(Error::new("Invalid base64 length")),
+        }
+        if bytes.len() != BYTES {
+            return Err(Error::new(&format!("Encoded grid must be {} bytes", BYTES)));
+        }
+        let bits: Vec<bool> = (0..BITS)
+            .map(|i| bytes[i / 8] & (0x80 >> (i % 8)) != 0)
+            .collect();
+        Grid::from_bits(&bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+
+    #[test]
+    fn test_candidate_string() {
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let s = grid.to_candidate_string();
+        assert_eq!(s.len(), 729);
+        assert_eq!(&s[..18], "---4-----123456789");
+        assert_eq!(Grid::from_candidate_string(&s).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_base64() {
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let s = grid.to_base64();
+        assert_eq!(s.len(), 136);
+        assert_eq!(Grid::from_base64(&s).unwrap(), grid);
+        assert_eq!(
+            Grid::from_base64(&Grid::new().to_base64()).unwrap(),
+            Grid::new()
+        );
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        assert!(Grid::from_candidate_string("123").is_err());
+        assert!(Grid::from_candidate_string(&"2".repeat(729)).is_err());
+        assert!(Grid::from_base64("AAAA").is_err());
+        assert!(Grid::from_base64(&"!".repeat(136)).is_err());
+        // Solved with two values, and a dash in an unsolved cell
+        assert!(Grid::from_base64(&"/".repeat(136)).is_err());
+        let s = Grid::new().to_candidate_string();
+        assert!(Grid::from_candidate_string(&s.replacen('1', "-", 1)).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! Puzzles shared by the unit tests.
+
+/// Solved with naked singles.
+pub const PUZZLE: &str =
+    "000120000900005060075040093092050007164030528300010640680090750020500001000082000";
+pub const SOLUTION: &str =
+    "436129875918375264275846193892654317164937528357218649683491752729563481541782936";
+/// Needs a naked pair, and several strategies in turn.
+pub const PAIR_PUZZLE: &str =
+    "400000038002004100005300240070609004020000070600703090057008300003900400240000009";
+/// A few naked singles from the end.
+pub const NEARLY_SOLVED: &str =
+    "036129875918375264275846193892654317164937528357218649683491752729563481541782930";
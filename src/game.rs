@@ -0,0 +1,285 @@
+//! State of an interactive game: the player's grid and its history, the
+//! cursor and hints. Front ends like the terminal player only handle input
+//! and drawing.
+//!
+//! Unsolved cells hold the player's pencilmarks. A cell with all nine
+//! candidates has no pencilmarks, which is also what the solver assumes.
+
+use crate::hint::{Hint, HintLevel};
+use crate::history::History;
+use crate::solver::strategies::all_strategies;
+use crate::solver::Solver;
+use crate::{Candidates, Cell, CellValue, Grid, Position};
+
+pub struct Game {
+    puzzle: Grid,
+    history: History,
+    cursor: Position,
+    pencil: bool,
+    hint: Option<(Hint, HintLevel)>,
+    solver: Solver,
+}
+
+impl Game {
+    pub fn new(puzzle: Grid) -> Self {
+        Self {
+            puzzle,
+            history: History::new(puzzle),
+            cursor: Position::new(1, 1),
+            pencil: false,
+            hint: None,
+            solver: Solver::new(all_strategies()),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        self.history.grid()
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    /// True if digits toggle pencilmarks instead of setting values.
+    pub fn is_pencil(&self) -> bool {
+        self.pencil
+    }
+
+    /// True for the clues of the puzzle, which cannot be changed.
+    pub fn is_given(&self, pos: Position) -> bool {
+        matches!(self.puzzle.get_cell(pos), Cell::Solved(_))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid().is_solved() && self.conflicts().is_empty()
+    }
+
+    /// Move the cursor, wrapping around the edges of the grid.
+    pub fn move_cursor(&mut self, dcol: i8, drow: i8) {
+        let wrap = |n: u8, d: i8| ((n as i8 - 1 + d).rem_euclid(9) + 1) as u8;
+        self.cursor = Position::new(wrap(self.cursor.col(), dcol), wrap(self.cursor.row(), drow));
+    }
+
+    pub fn toggle_pencil(&mut self) {
+        self.pencil = !self.pencil;
+    }
+
+    /// A digit typed at the cursor, a value or a pencilmark depending on the mode.
+    pub fn input(&mut self, v: CellValue) {
+        if self.pencil {
+            self.toggle_mark(self.cursor, v);
+        } else {
+            self.set_value(self.cursor, v);
+        }
+    }
+
+    /// Update a cell in the history. Clues never change.
+    fn update(&mut self, label: &str, pos: Position, cell: Cell) {
+        if self.is_given(pos) {
+            return;
+        }
+        // Not set_cell, a single pencilmark is not a value
+        if self
+            .history
+            .record(label, |grid| grid.cells[Grid::index(&pos)] = cell)
+        {
+            self.hint = None;
+        }
+    }
+
+    pub fn set_value(&mut self, pos: Position, v: CellValue) {
+        self.update(&format!("{}={}", pos, u8::from(v)), pos, Cell::Solved(v));
+    }
+
+    /// Toggle a pencilmark. Marking a cell without pencilmarks leaves only
+    /// that mark, and removing the last mark clears the pencilmarks.
+    pub fn toggle_mark(&mut self, pos: Position, v: CellValue) {
+        let mut candidates = match self.grid().get_cell(pos) {
+            Cell::Unsolved(c) if c.count() < 9 => c,
+            _ => Candidates::new([false; 9]),
+        };
+        if candidates.can_be(&v) {
+            candidates.remove(&v);
+        } else {
+            candidates.add(&v);
+        }
+        if candidates.count() == 0 {
+            candidates = Candidates::new([true; 9]);
+        }
+        self.update(
+            &format!("{} marks {}", pos, candidates),
+            pos,
+            Cell::Unsolved(candidates),
+        );
+    }
+
+    /// Clear the value or pencilmarks of a cell.
+    pub fn clear(&mut self, pos: Position) {
+        let cell = Cell::Unsolved(Candidates::new([true; 9]));
+        self.update(&format!("{} cleared", pos), pos, cell);
+    }
+
+    /// Go back to the grid before the last change, false if there is none.
+    pub fn undo(&mut self) -> bool {
+        self.hint = None;
+        self.history.undo()
+    }
+
+    /// Make an undone change again, false if there is none.
+    pub fn redo(&mut self) -> bool {
+        self.hint = None;
+        self.history.redo()
+    }
+
+    /// Set the pencilmarks of every unsolved cell to the values not seen
+    /// among the solved cells of its row, column and box.
+    pub fn autofill(&mut self) {
+        let before = *self.grid();
+        let changed = self.history.record("Candidates", |grid| {
+            for pos in Position::row_vecs().into_iter().flatten() {
+                if let Cell::Solved(_) = before.get_cell(pos) {
+                    continue;
+                }
+                let mut candidates = Candidates::new([true; 9]);
+                for seen in pos.seen_vec(false) {
+                    if let Cell::Solved(v) = before.get_cell(seen) {
+                        candidates.remove(&v);
+                    }
+                }
+                grid.cells[Grid::index(&pos)] = Cell::Unsolved(candidates);
+            }
+        });
+        if changed {
+            self.hint = None;
+        }
+    }
+
+    /// Solved cells with the same value as a solved cell they see.
+    pub fn conflicts(&self) -> Vec<Position> {
+        Position::row_vecs()
+            .into_iter()
+            .flatten()
+            .filter(|pos| match self.grid().get_cell(*pos) {
+                Cell::Solved(v) => pos
+                    .seen_vec(false)
+                    .into_iter()
+                    .any(|seen| self.grid().get_cell(seen) == Cell::Solved(v)),
+                Cell::Unsolved(_) => false,
+            })
+            .collect()
+    }
+
+    /// Ask for a hint, each call reveals more of it up to the full step.
+    pub fn hint(&mut self) -> Option<Hint> {
+        let next = match self.hint.take() {
+            Some((hint, HintLevel::Technique)) => Some((hint, HintLevel::Region)),
+            Some((hint, _)) => Some((hint, HintLevel::Full)),
+            None => Hint::find(&self.solver, self.grid()).map(|h| (h, HintLevel::Technique)),
+        };
+        self.hint = next;
+        self.hint
+            .as_ref()
+            .map(|(hint, level)| hint.at_level(*level))
+    }
+
+    /// The hint revealed so far, if the grid has not changed since.
+    pub fn current_hint(&self) -> Option<Hint> {
+        self.hint
+            .as_ref()
+            .map(|(hint, level)| hint.at_level(*level))
+    }
+
+    /// Apply the current hint, whatever was revealed of it.
+    pub fn apply_hint(&mut self) -> bool {
+        match self.hint.take() {
+            Some((hint, _)) => self.history.apply(&hint.strategy, &hint.delta.unwrap()),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+
+    fn game() -> Game {
+        Game::new(Grid::from_str(PAIR_PUZZLE).unwrap())
+    }
+
+    #[test]
+    fn test_values_and_undo() {
+        let mut game = game();
+        game.move_cursor(1, 0);
+        game.input(6.into());
+        assert_eq!(
+            game.grid().get_cell(Position::new(2, 1)),
+            Cell::Solved(6.into())
+        );
+        game.move_cursor(-1, 0);
+        game.input(5.into());
+        assert_eq!(
+            game.grid().get_cell(Position::new(1, 1)),
+            Cell::Solved(4.into())
+        );
+        assert!(game.undo());
+        assert_eq!(*game.grid(), Grid::from_str(PAIR_PUZZLE).unwrap());
+        assert!(!game.undo());
+        assert!(game.redo());
+        assert_eq!(
+            game.grid().get_cell(Position::new(2, 1)),
+            Cell::Solved(6.into())
+        );
+    }
+
+    #[test]
+    fn test_pencilmarks() {
+        let mut game = game();
+        let pos = Position::new(2, 1);
+        game.toggle_mark(pos, 3.into());
+        assert_eq!(game.grid().get_cell(pos).candidates(), Some(vec![3.into()]));
+        game.toggle_mark(pos, 6.into());
+        game.toggle_mark(pos, 3.into());
+        assert_eq!(game.grid().get_cell(pos).candidates(), Some(vec![6.into()]));
+        game.toggle_mark(pos, 6.into());
+        assert_eq!(game.grid().get_cell(pos).candidates().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut game = game();
+        game.set_value(Position::new(2, 1), 3.into());
+        let conflicts = game.conflicts();
+        assert!(conflicts.contains(&Position::new(2, 1)));
+        assert!(conflicts.contains(&Position::new(8, 1)));
+        assert_eq!(conflicts.len(), 2);
+    }
+
+    #[test]
+    fn test_autofill() {
+        let mut game = game();
+        game.autofill();
+        assert_eq!(
+            game.grid().get_cell(Position::new(2, 1)).candidates(),
+            Some(vec![1.into(), 6.into(), 9.into()])
+        );
+    }
+
+    #[test]
+    fn test_progressive_hint() {
+        let mut game = game();
+        assert_eq!(game.hint().unwrap().region, None);
+        assert!(game.hint().unwrap().region.is_some());
+        assert!(game.hint().unwrap().delta.is_some());
+        assert!(game.apply_hint());
+        assert!(game.current_hint().is_none());
+        assert!(game.undo());
+        assert_eq!(*game.grid(), Grid::from_str(PAIR_PUZZLE).unwrap());
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::solver::backtrack::{has_unique_solution, random_solution};
+use crate::solver::Solver;
+use crate::{Candidates, Cell, Error, Grid, Position, Result};
+
+/// Number of complete grids tried before giving up on a constraint.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Symmetry of the clue positions of a generated puzzle.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// 180° rotation around the center cell
+    Rotational,
+    /// Mirror across the main diagonal
+    Diagonal,
+    /// Mirror across the middle row
+    Horizontal,
+    /// Mirror across the middle column
+    Vertical,
+}
+
+impl Symmetry {
+    /// Position that must be a clue whenever pos is a clue.
+    pub fn mirror(&self, pos: Position) -> Position {
+        let (col, row) = (pos.col(), pos.row());
+        match self {
+            Self::None => pos,
+            Self::Rotational => Position::new(10 - col, 10 - row),
+            Self::Diagonal => Position::new(row, col),
+            Self::Horizontal => Position::new(col, 10 - row),
+            Self::Vertical => Position::new(10 - col, row),
+        }
+    }
+
+    /// Groups of positions which are kept or removed together.
+    fn orbits(&self) -> Vec<Vec<Position>> {
+        let mut seen = HashSet::new();
+        let mut orbits = vec![];
+        for pos in Position::row_vecs().into_iter().flatten() {
+            if seen.contains(&pos) {
+                continue;
+            }
+            let mut orbit = vec![pos];
+            let other = self.mirror(pos);
+            if other != pos {
+                orbit.push(other);
+            }
+            seen.extend(orbit.iter().cloned());
+            orbits.push(orbit);
+        }
+        orbits
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::None => "none",
+                Self::Rotational => "rotational",
+                Self::Diagonal => "diagonal",
+                Self::Horizontal => "horizontal",
+                Self::Vertical => "vertical",
+            }
+        )
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(Self::None),
+            "rotational" | "180" => Ok(Self::Rotational),
+            "diagonal" => Ok(Self::Diagonal),
+            "horizontal" => Ok(Self::Horizontal),
+            "vertical" => Ok(Self::Vertical),
+            _ => Err(Error::new(&format!("Unknown symmetry: {}", s))),
+        }
+    }
+}
+
+/// Positions where a generated puzzle is allowed to have clues.
+/// Parsed from 81 characters in row order, where 'x', 'X', '#', '*' or '1'
+/// allow a clue and '.', '0' or '-' forbid it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ClueMask([bool; 81]);
+
+impl ClueMask {
+    pub fn allows(&self, pos: Position) -> bool {
+        self.0[Grid::index(&pos)]
+    }
+
+    fn is_symmetric(&self, symmetry: Symmetry) -> bool {
+        Position::row_vecs()
+            .into_iter()
+            .flatten()
+            .all(|p| self.allows(p) == self.allows(symmetry.mirror(p)))
+    }
+}
+
+impl FromStr for ClueMask {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let mut mask = [false; 81];
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.len() != 81 {
+            return Err(Error::new("Clue mask must have 81 characters"));
+        }
+        for (i, c) in chars.into_iter().enumerate() {
+            mask[i] = match c {
+                'x' | 'X' | '#' | '*' | '1' => true,
+                '.' | '0' | '-' => false,
+                _ => return Err(Error::new(&format!("Invalid clue mask character: {}", c))),
+            };
+        }
+        Ok(Self(mask))
+    }
+}
+
+impl fmt::Display for ClueMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0.iter() {
+            write!(f, "{}", if *b { 'x' } else { '.' })?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates puzzles with a unique solution by removing clues from a random
+/// complete grid for as long as the solution stays unique.
+pub struct Generator {
+    symmetry: Symmetry,
+    mask: Option<ClueMask>,
+    rng: StdRng,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self {
+            symmetry: Symmetry::None,
+            mask: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    pub fn with_mask(mut self, mask: ClueMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn generate(&mut self) -> Result<Grid> {
+        if let Some(mask) = self.mask {
+            if !mask.is_symmetric(self.symmetry) {
+                return Err(Error::new(&format!(
+                    "Clue mask does not have {} symmetry",
+                    self.symmetry
+                )));
+            }
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let solution = random_solution(&mut self.rng);
+            if let Some(puzzle) = self.remove_clues(solution) {
+                return Ok(puzzle);
+            }
+        }
+        Err(Error::new(
+            "Could not generate a puzzle with a unique solution for this clue mask",
+        ))
+    }
+
+    fn remove_clues(&mut self, solution: Grid) -> Option<Grid> {
+        let mut puzzle = solution;
+        let (mut allowed, forbidden): (Vec<Vec<Position>>, Vec<Vec<Position>>) = self
+            .symmetry
+            .orbits()
+            .into_iter()
+            .partition(|orbit| self.mask.is_none_or(|m| m.allows(orbit[0])));
+        for pos in forbidden.into_iter().flatten() {
+            clear(&mut puzzle, pos);
+        }
+        if !has_unique_solution(&puzzle) {
+            return None;
+        }
+        allowed.shuffle(&mut self.rng);
+        for orbit in allowed {
+            let saved = puzzle;
+            for pos in &orbit {
+                clear(&mut puzzle, *pos);
+            }
+            if !has_unique_solution(&puzzle) {
+                puzzle = saved;
+            }
+        }
+        Some(puzzle)
+    }
+}
+
+fn clear(grid: &mut Grid, pos: Position) {
+    grid.set_cell(pos, Cell::Unsolved(Candidates::new([true; 9])));
+}
+
+fn clues(grid: &Grid) -> Vec<Position> {
+    Position::row_vecs()
+        .into_iter()
+        .flatten()
+        .filter(|p| matches!(grid.get_cell(*p), Cell::Solved(_)))
+        .collect()
+}
+
+fn solves(solver: &Solver, grid: &Grid) -> bool {
+    let mut grid = *grid;
+    solver.solve(&mut grid);
+    grid.is_solved()
+}
+
+/// A puzzle is minimal if it has a unique solution and removing any one
+/// of its clues makes the solution ambiguous.
+pub fn is_minimal(grid: &Grid) -> bool {
+    if !has_unique_solution(grid) {
+        return false;
+    }
+    clues(grid).into_iter().all(|pos| {
+        let mut reduced = *grid;
+        clear(&mut reduced, pos);
+        !has_unique_solution(&reduced)
+    })
+}
+
+/// Remove redundant clues from a puzzle, in row order, until it is minimal.
+/// If a solver is given, a clue is only removed if the solver can still
+/// solve the puzzle without it, so the result is minimal for that solver.
+pub fn reduce(grid: &Grid, solver: Option<&Solver>) -> Result<Grid> {
+    if !has_unique_solution(grid) {
+        return Err(Error::new("Puzzle does not have a unique solution"));
+    }
+    if let Some(solver) = solver {
+        if !solves(solver, grid) {
+            return Err(Error::new("Puzzle cannot be solved with these strategies"));
+        }
+    }
+    let mut puzzle = *grid;
+    for pos in clues(grid) {
+        let mut reduced = puzzle;
+        clear(&mut reduced, pos);
+        if has_unique_solution(&reduced) && solver.is_none_or(|s| solves(s, &reduced)) {
+            puzzle = reduced;
+        }
+    }
+    Ok(puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::fixtures::PUZZLE;
+
+    use crate::solver::strategies::all_strategies;
+
+    // Naked single test puzzle from the solver tests, 33 clues
+
+    fn clue_positions(grid: &Grid) -> HashSet<Position> {
+        clues(grid).into_iter().collect()
+    }
+
+    #[test_case(Symmetry::None; "none")]
+    #[test_case(Symmetry::Rotational; "rotational")]
+    #[test_case(Symmetry::Diagonal; "diagonal")]
+    #[test_case(Symmetry::Horizontal; "horizontal")]
+    #[test_case(Symmetry::Vertical; "vertical")]
+    fn test_generate_symmetric(symmetry: Symmetry) {
+        let puzzle = Generator::new()
+            .with_seed(7)
+            .with_symmetry(symmetry)
+            .generate()
+            .unwrap();
+        assert!(has_unique_solution(&puzzle));
+        let clues = clue_positions(&puzzle);
+        for p in &clues {
+            assert!(clues.contains(&symmetry.mirror(*p)));
+        }
+    }
+
+    #[test]
+    fn test_generate_with_mask() {
+        let mask_str: String = (0..81)
+            .map(|i| if i % 10 == 0 { '.' } else { 'x' })
+            .collect();
+        let mask = ClueMask::from_str(&mask_str).unwrap();
+        let puzzle = Generator::new()
+            .with_seed(3)
+            .with_mask(mask)
+            .generate()
+            .unwrap();
+        assert!(has_unique_solution(&puzzle));
+        for p in clue_positions(&puzzle) {
+            assert!(mask.allows(p));
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_mask_is_rejected() {
+        let mask = ClueMask::from_str(&format!("x{}", ".".repeat(80))).unwrap();
+        assert!(Generator::new()
+            .with_symmetry(Symmetry::Rotational)
+            .with_mask(mask)
+            .generate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_generate_is_seeded() {
+        let a = Generator::new().with_seed(11).generate().unwrap();
+        let b = Generator::new().with_seed(11).generate().unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_generated_puzzle_is_minimal() {
+        let puzzle = Generator::new().with_seed(5).generate().unwrap();
+        assert!(is_minimal(&puzzle));
+    }
+
+    #[test]
+    fn test_reduce() {
+        let grid = Grid::from_str(PUZZLE).unwrap();
+        assert!(!is_minimal(&grid));
+        let reduced = reduce(&grid, None).unwrap();
+        assert!(is_minimal(&reduced));
+        assert!(clue_positions(&reduced).is_subset(&clue_positions(&grid)));
+    }
+
+    #[test]
+    fn test_reduce_keeps_solvable() {
+        let solver = Solver::new(all_strategies());
+        let original = Grid::from_str(PUZZLE).unwrap();
+        // Put one redundant clue back into a minimal puzzle
+        let mut grid = reduce(&original, None).unwrap();
+        let extra = clue_positions(&original)
+            .difference(&clue_positions(&grid))
+            .copied()
+            .min()
+            .unwrap();
+        grid.set_cell(extra, original.get_cell(extra));
+        let reduced = reduce(&grid, Some(&solver)).unwrap();
+        assert!(solves(&solver, &reduced));
+        assert!(clue_positions(&reduced).len() < clue_positions(&grid).len());
+    }
+
+    #[test]
+    fn test_reduce_requires_unique_solution() {
+        assert!(reduce(&Grid::new(), None).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! The candidate link graph of a grid, as chain strategies see it, with an
+//! export to Graphviz DOT for debugging them.
+//!
+//! Nodes are the candidates of unsolved cells. Two candidates of a digit
+//! are strongly linked when they are its only two places in a row, column
+//! or box (a conjugate pair), and weakly linked when they otherwise share a
+//! unit. Two candidates of the same cell are weakly linked.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::hint::Region;
+use crate::solver::strategies::Link;
+use crate::{CellValue, Grid, Position};
+
+/// Inches between cells in the DOT layout, and between candidates in a cell.
+const CELL: f64 = 1.8;
+const CANDIDATE: f64 = 0.55;
+
+pub struct LinkGraph {
+    nodes: Vec<(Position, CellValue)>,
+    links: Vec<Link>,
+}
+
+impl LinkGraph {
+    pub fn new(grid: &Grid) -> Self {
+        let nodes: Vec<(Position, CellValue)> = Position::grid_vec()
+            .into_iter()
+            .flat_map(|pos| {
+                grid.get_cell(pos)
+                    .candidates()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |v| (pos, v))
+            })
+            .collect();
+        let mut strong = HashSet::new();
+        for unit in Position::unit_vecs() {
+            for n in 1..=9 {
+                let v = CellValue::new(n);
+                let places: Vec<Position> = unit
+                    .iter()
+                    .copied()
+                    .filter(|&p| nodes.contains(&(p, v)))
+                    .collect();
+                if let [a, b] = places[..] {
+                    strong.insert((a.min(b), a.max(b), v));
+                }
+            }
+        }
+        let mut links = vec![];
+        for (i, &(a, u)) in nodes.iter().enumerate() {
+            for &(b, v) in &nodes[i + 1..] {
+                let link = if a == b {
+                    Some(false)
+                } else if u == v && a.seen_vec(false).contains(&b) {
+                    Some(strong.contains(&(a.min(b), a.max(b), u)))
+                } else {
+                    None
+                };
+                if let Some(strong) = link {
+                    links.push(Link {
+                        from: a,
+                        from_value: u,
+                        to: b,
+                        to_value: v,
+                        strong,
+                    });
+                }
+            }
+        }
+        Self { nodes, links }
+    }
+
+    /// Candidates in row order, then by digit.
+    pub fn nodes(&self) -> &[(Position, CellValue)] {
+        &self.nodes
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Only the candidates of these digits.
+    pub fn with_digits(mut self, digits: &[CellValue]) -> Self {
+        self.retain(|_, v| digits.contains(&v));
+        self
+    }
+
+    /// Only the candidates in a row, column or box.
+    pub fn with_region(mut self, region: Region) -> Self {
+        let positions = region.positions();
+        self.retain(|pos, _| positions.contains(&pos));
+        self
+    }
+
+    fn retain(&mut self, keep: impl Fn(Position, CellValue) -> bool) {
+        self.nodes.retain(|&(pos, v)| keep(pos, v));
+        self.links
+            .retain(|l| keep(l.from, l.from_value) && keep(l.to, l.to_value));
+    }
+
+    /// The graph in DOT. Nodes are placed like the candidates in the grid
+    /// when laid out with neato; strong links are solid, weak links dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph candidates {\n");
+        dot.push_str("  node [shape=circle, fixedsize=true, width=0.45, fontsize=8];\n");
+        for &(pos, v) in &self.nodes {
+            let n = u8::from(v);
+            let x = (pos.col() - 1) as f64 * CELL + ((n - 1) % 3) as f64 * CANDIDATE;
+            let y = (9 - pos.row()) as f64 * CELL + (2 - (n - 1) / 3) as f64 * CANDIDATE;
+            writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\n{}\", pos=\"{:.2},{:.2}!\"];",
+                node_id(pos, v),
+                pos,
+                n,
+                x,
+                y
+            )
+            .unwrap();
+        }
+        for link in &self.links {
+            let style = if link.strong {
+                "color=\"#1F6FD1\", penwidth=2"
+            } else {
+                "color=\"#999999\", style=dashed"
+            };
+            writeln!(
+                dot,
+                "  \"{}\" -- \"{}\" [{}];",
+                node_id(link.from, link.from_value),
+                node_id(link.to, link.to_value),
+                style
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn node_id(pos: Position, v: CellValue) -> String {
+    format!("{}#{}", pos, u8::from(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::Cell;
+
+    fn grid_with(cells: &[(Position, &[u8])]) -> Grid {
+        let mut grid = Grid::from_str(&"1".repeat(81)).unwrap();
+        for &(pos, candidates) in cells {
+            let mut possible = [false; 9];
+            for &n in candidates {
+                possible[n as usize - 1] = true;
+            }
+            grid.cells[Grid::index(&pos)] = Cell::Unsolved(crate::Candidates::new(possible));
+        }
+        grid
+    }
+
+    #[test]
+    fn test_links() {
+        // 5 in row 1 only at R1C1 and R1C5, 3 seen three times in box 1
+        let grid = grid_with(&[
+            (Position::new(1, 1), &[3, 5]),
+            (Position::new(5, 1), &[5]),
+            (Position::new(2, 2), &[3]),
+            (Position::new(3, 3), &[3]),
+        ]);
+        let graph = LinkGraph::new(&grid);
+        assert_eq!(graph.nodes().len(), 5);
+        let links: Vec<(String, String, bool)> = graph
+            .links()
+            .iter()
+            .map(|l| {
+                (
+                    node_id(l.from, l.from_value),
+                    node_id(l.to, l.to_value),
+                    l.strong,
+                )
+            })
+            .collect();
+        assert!(links.contains(&("R1C1#3".into(), "R1C1#5".into(), false)));
+        assert!(links.contains(&("R1C1#5".into(), "R1C5#5".into(), true)));
+        assert!(links.contains(&("R1C1#3".into(), "R2C2#3".into(), false)));
+        // The cell link, the 5s, and the three 3s of box 1 all see each other
+        assert_eq!(links.len(), 5);
+    }
+
+    #[test]
+    fn test_row_9() {
+        // 7 in column 9 at R1C9, R8C9 and R9C9, and only R8C9, R9C9 in box 9
+        let grid = grid_with(&[
+            (Position::new(9, 1), &[7]),
+            (Position::new(9, 8), &[7]),
+            (Position::new(9, 9), &[2, 7]),
+        ]);
+        let graph = LinkGraph::new(&grid);
+        assert_eq!(graph.nodes().len(), 4);
+        assert_eq!(graph.nodes()[3], (Position::new(9, 9), CellValue::new(7)));
+        let strong: Vec<(Position, Position)> = graph
+            .links()
+            .iter()
+            .filter(|l| l.strong)
+            .map(|l| (l.from, l.to))
+            .collect();
+        assert_eq!(strong, vec![(Position::new(9, 8), Position::new(9, 9))]);
+        // Three places in column 9 make weak links only
+        assert!(graph
+            .links()
+            .iter()
+            .filter(|l| l.from == Position::new(9, 1))
+            .all(|l| !l.strong));
+        let graph = LinkGraph::new(&grid).with_region(Region::Row(9));
+        assert_eq!(graph.nodes().len(), 2);
+        assert_eq!(graph.links().len(), 1);
+    }
+
+    #[test]
+    fn test_node_order() {
+        let positions: Vec<Position> = LinkGraph::new(&Grid::new())
+            .nodes()
+            .iter()
+            .map(|&(pos, _)| pos)
+            .step_by(9)
+            .collect();
+        assert_eq!(positions, Position::row_vecs().concat());
+    }
+
+    #[test]
+    fn test_filters_and_dot() {
+        let grid = grid_with(&[
+            (Position::new(1, 1), &[3, 5]),
+            (Position::new(5, 1), &[5]),
+            (Position::new(1, 4), &[3]),
+        ]);
+        let graph = LinkGraph::new(&grid).with_digits(&[CellValue::new(3)]);
+        assert_eq!(graph.nodes().len(), 2);
+        assert!(graph.links().iter().all(|l| l.strong));
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph candidates {\n"));
+        assert!(dot.contains("\"R1C1#3\" -- \"R4C1#3\" [color=\"#1F6FD1\", penwidth=2];"));
+        let graph = LinkGraph::new(&grid).with_region(Region::Row(1));
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.links().len(), 2);
+    }
+}
@@ -0,0 +1,232 @@
+//! Hints: the next step of the solver, revealed progressively.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::solver::strategies::{Difficulty, StrategyDelta};
+use crate::solver::Solver;
+use crate::{Error, Grid, Position, Result};
+
+/// How much of a hint to reveal.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum HintLevel {
+    /// The name of the strategy
+    Technique,
+    /// The strategy and the row, column or box where it applies
+    Region,
+    /// The strategy, the region and the cells solved or candidates eliminated
+    Full,
+}
+
+impl FromStr for HintLevel {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" | "technique" => Ok(Self::Technique),
+            "2" | "region" => Ok(Self::Region),
+            "3" | "full" => Ok(Self::Full),
+            _ => Err(Error::new(&format!("Unknown hint level: {}", s))),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Region {
+    Row(u8),
+    Column(u8),
+    Box(u8),
+}
+
+impl Region {
+    /// The row, column or box made of these positions.
+    fn of(unit: &[Position]) -> Self {
+        let first = unit[0];
+        if unit.iter().all(|p| p.row() == first.row()) {
+            Self::Row(first.row())
+        } else if unit.iter().all(|p| p.col() == first.col()) {
+            Self::Column(first.col())
+        } else {
+            Self::Box((first.row() - 1) / 3 * 3 + (first.col() - 1) / 3 + 1)
+        }
+    }
+
+    /// The positions of the row, column or box.
+    pub fn positions(&self) -> Vec<Position> {
+        match *self {
+            Self::Row(n) => Position::new(1, n).row_vec(true),
+            Self::Column(n) => Position::new(n, 1).col_vec(true),
+            Self::Box(n) => Position::new((n - 1) % 3 * 3 + 1, (n - 1) / 3 * 3 + 1).box_vec(true),
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = Error;
+    /// "row 3", "column 3" or "box 3", or short as "r3", "c3" or "b3".
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.to_lowercase();
+        let split = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+        let (kind, n) = s.split_at(split);
+        let n = match n.parse::<u8>() {
+            Ok(n) if (1..=9).contains(&n) => n,
+            _ => return Err(Error::new(&format!("Unknown region: {}", s))),
+        };
+        match kind.trim() {
+            "r" | "row" => Ok(Self::Row(n)),
+            "c" | "col" | "column" => Ok(Self::Column(n)),
+            "b" | "box" => Ok(Self::Box(n)),
+            _ => Err(Error::new(&format!("Unknown region: {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Row(n) => write!(f, "row {}", n),
+            Self::Column(n) => write!(f, "column {}", n),
+            Self::Box(n) => write!(f, "box {}", n),
+        }
+    }
+}
+
+/// The easiest step the solver can take, limited to a single unit when the
+/// strategy works unit by unit.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hint {
+    pub strategy: String,
+    pub difficulty: Difficulty,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub region: Option<Region>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub delta: Option<StrategyDelta>,
+}
+
+impl Hint {
+    /// The next hint for a grid, None if no strategy applies.
+    /// Candidates already eliminated in the grid are taken into account.
+    pub fn find(solver: &Solver, grid: &Grid) -> Option<Hint> {
+        let step = solver.solve_step(grid)?;
+        let (region, delta) = match step.strategy.solve_first_unit(grid) {
+            Some((unit, delta)) => (Some(Region::of(&unit)), delta),
+            None => (None, step.delta),
+        };
+        Some(Hint {
+            strategy: step.strategy.name(),
+            difficulty: step.strategy.difficulty(),
+            region,
+            delta: Some(delta),
+        })
+    }
+
+    /// The hint with only what the level reveals.
+    pub fn at_level(&self, level: HintLevel) -> Hint {
+        Hint {
+            strategy: self.strategy.clone(),
+            difficulty: self.difficulty,
+            region: self.region.filter(|_| level >= HintLevel::Region),
+            delta: self.delta.clone().filter(|_| level >= HintLevel::Full),
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.strategy)?;
+        if let Some(region) = self.region {
+            write!(f, " in {}", region)?;
+        }
+        if let Some(delta) = &self.delta {
+            write!(f, ": {}", delta)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+
+    #[test]
+    fn test_hint_is_one_unit() {
+        let solver = Solver::new(all_strategies());
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let hint = Hint::find(&solver, &grid).unwrap();
+        assert_eq!(hint.strategy, "Naked Single");
+        assert_eq!(hint.region, Some(Region::Row(1)));
+        let delta = hint.delta.unwrap();
+        assert!(delta.eliminated().iter().all(|(p, _)| p.row() == 1));
+    }
+
+    #[test]
+    fn test_region_positions() {
+        for unit in Position::unit_vecs() {
+            let mut positions = Region::of(&unit).positions();
+            let mut unit = unit;
+            positions.sort();
+            unit.sort();
+            assert_eq!(positions, unit);
+        }
+    }
+
+    #[test]
+    fn test_parse_region() {
+        assert_eq!(Region::from_str("row 3").unwrap(), Region::Row(3));
+        assert_eq!(Region::from_str("C7").unwrap(), Region::Column(7));
+        assert_eq!(Region::from_str("b9").unwrap(), Region::Box(9));
+        assert!(Region::from_str("b0").is_err());
+        assert!(Region::from_str("x1").is_err());
+    }
+
+    #[test]
+    fn test_hint_levels() {
+        let solver = Solver::new(all_strategies());
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let hint = Hint::find(&solver, &grid).unwrap();
+        assert_eq!(
+            hint.at_level(HintLevel::Technique).to_string(),
+            "Naked Single"
+        );
+        assert_eq!(
+            hint.at_level(HintLevel::Region).to_string(),
+            "Naked Single in row 1"
+        );
+        assert!(hint
+            .at_level(HintLevel::Full)
+            .to_string()
+            .starts_with("Naked Single in row 1: R1C"));
+    }
+
+    #[test]
+    fn test_full_hint_in_row_order() {
+        let solver = Solver::new(all_strategies());
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let hint = Hint::find(&solver, &grid).unwrap();
+        let text = hint.to_string();
+        let cols: Vec<u8> = text
+            .split("R1C")
+            .skip(1)
+            .map(|s| s[..1].parse().unwrap())
+            .collect();
+        assert!(cols.len() > 1);
+        assert!(cols.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_no_hint_for_solved_grid() {
+        let solver = Solver::new(all_strategies());
+        let grid = Grid::from_str(
+            "436129875918375264275846193892654317164937528357218649683491752729563481541782936",
+        )
+        .unwrap();
+        assert_eq!(Hint::find(&solver, &grid), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! Undo and redo for a grid being edited or solved.
+//!
+//! Each change keeps the cells it touched as they were before and after,
+//! so it can be undone exactly, whatever made it: a solver step, a value
+//! typed by a player or a pencilmark.
+
+use crate::solver::strategies::StrategyDelta;
+use crate::{Cell, Grid, Position};
+
+struct Change {
+    label: String,
+    /// (position, before, after) for every cell that changed
+    cells: Vec<(Position, Cell, Cell)>,
+}
+
+/// A grid and the changes made to it. Step n is the grid after the first n
+/// changes, step 0 being the initial grid.
+pub struct History {
+    grid: Grid,
+    changes: Vec<Change>,
+    step: usize,
+}
+
+impl History {
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            grid,
+            changes: vec![],
+            step: 0,
+        }
+    }
+
+    /// The grid at the current step.
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Number of changes, undone ones included until a new change replaces them.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Label of the change that leads to a step, None for step 0.
+    pub fn label(&self, step: usize) -> Option<&str> {
+        step.checked_sub(1)
+            .and_then(|i| self.changes.get(i))
+            .map(|c| c.label.as_str())
+    }
+
+    /// Change the grid with f and record the change, dropping the changes
+    /// that were undone. Returns false if f changed nothing.
+    pub fn record<F: FnOnce(&mut Grid)>(&mut self, label: &str, f: F) -> bool {
+        let mut after = self.grid;
+        f(&mut after);
+        let cells: Vec<(Position, Cell, Cell)> = Position::row_vecs()
+            .into_iter()
+            .flatten()
+            .map(|p| (p, self.grid.get_cell(p), after.get_cell(p)))
+            .filter(|(_, before, after)| before != after)
+            .collect();
+        if cells.is_empty() {
+            return false;
+        }
+        self.changes.truncate(self.step);
+        self.changes.push(Change {
+            label: label.to_string(),
+            cells,
+        });
+        self.grid = after;
+        self.step += 1;
+        true
+    }
+
+    /// Apply a solver delta as a change.
+    pub fn apply(&mut self, label: &str, delta: &StrategyDelta) -> bool {
+        self.record(label, |grid| delta.apply(grid))
+    }
+
+    /// Go back one step, false if at the initial grid.
+    pub fn undo(&mut self) -> bool {
+        if self.step == 0 {
+            return false;
+        }
+        self.step -= 1;
+        for (pos, before, _) in &self.changes[self.step].cells {
+            self.grid.cells[Grid::index(pos)] = *before;
+        }
+        true
+    }
+
+    /// Go forward one step, false if there is no undone change.
+    pub fn redo(&mut self) -> bool {
+        if self.step == self.changes.len() {
+            return false;
+        }
+        for (pos, _, after) in &self.changes[self.step].cells {
+            self.grid.cells[Grid::index(pos)] = *after;
+        }
+        self.step += 1;
+        true
+    }
+
+    /// Go to a step, false if it is past the last change.
+    pub fn jump(&mut self, step: usize) -> bool {
+        if step > self.changes.len() {
+            return false;
+        }
+        while self.step > step {
+            self.undo();
+        }
+        while self.step < step {
+            self.redo();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::Solver;
+
+    fn solved_history() -> (Vec<Grid>, History) {
+        let solver = Solver::new(all_strategies());
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let mut history = History::new(grid);
+        let mut grids = vec![grid];
+        for _ in 0..5 {
+            let step = solver.solve_step(&grid).unwrap();
+            step.delta.apply(&mut grid);
+            assert!(history.apply(&step.strategy.name(), &step.delta));
+            grids.push(grid);
+        }
+        (grids, history)
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let (grids, mut history) = solved_history();
+        assert_eq!(history.step(), 5);
+        assert!(!history.redo());
+        for i in (0..5).rev() {
+            assert!(history.undo());
+            assert_eq!(*history.grid(), grids[i]);
+        }
+        assert!(!history.undo());
+        assert!(history.redo());
+        assert_eq!(*history.grid(), grids[1]);
+        assert_eq!(history.label(1), Some("Naked Single"));
+        assert_eq!(history.label(0), None);
+    }
+
+    #[test]
+    fn test_jump() {
+        let (grids, mut history) = solved_history();
+        assert!(history.jump(2));
+        assert_eq!(*history.grid(), grids[2]);
+        assert!(history.jump(4));
+        assert_eq!(*history.grid(), grids[4]);
+        assert!(!history.jump(6));
+        assert_eq!(history.step(), 4);
+    }
+
+    #[test]
+    fn test_record_drops_undone_changes() {
+        let (grids, mut history) = solved_history();
+        history.jump(1);
+        let pos = Position::new(2, 1);
+        assert!(history.record("value", |g| g.set_cell(pos, Cell::Solved(6.into()))));
+        assert_eq!((history.step(), history.len()), (2, 2));
+        assert!(!history.record("same value", |g| g.set_cell(pos, Cell::Solved(6.into()))));
+        assert!(history.undo());
+        assert_eq!(*history.grid(), grids[1]);
+    }
+}
@@ -1,90 +1,228 @@
+//! HTML rendering of grids and solves with Tera templates.
+//!
+//! The built-in templates can be replaced by those of a directory, see
+//! `Templates::from_dir`. Each template is rendered with this context:
+//!
+//! - `number.html`, a solved cell: `cell`, its value, `placed`, true if the
+//!   step solved it, and `pattern`, true if the cell is part of the pattern
+//!   behind the step
+//! - `mark.html`, an unsolved cell: `mcells` and `eliminated`, 9 booleans
+//!   each for the candidates 1 to 9 left in the cell and removed by the
+//!   step, and `pattern`
+//! - `grid.html`: `cells`, the 81 rendered cells in row order
+//! - `sudoku.html`, a grid on its own: `cells`, and `delta`, the step in the
+//!   JSON schema of `src/serialize.rs` when there is one
+//! - `sudoku_step.html`, one page of a solve: the `sudoku.html` context with
+//!   `step`, the step number from 0, `strategy`, its caption, and
+//!   `link_prev` and `link_next`, the file names of the pages before and
+//!   after or `""`
+//! - `report.html`, a whole solve on one page: `steps`, a list of
+//!   `{"label": ..., "description": ..., "cells": [...]}` starting with the
+//!   puzzle as given
+//! - `header.html` and `footer.html` are included by the pages, with their
+//!   context
+//!
+//! Autoescaping is off since cells are rendered to HTML before the grid.
+
 use std::fs;
 use std::include_str;
+use std::str::FromStr;
 
 use lazy_static::lazy_static;
 use tera::{Context, Tera};
 
-use crate::solver::strategies::Strategy;
+use crate::solver::strategies::{
+    all_strategies, strategy_description, CellChange, Strategy, StrategyDelta,
+};
 use crate::solver::Solver;
-use crate::{Cell, Grid};
+use crate::{Cell, Error, Grid, Position, Result};
+
+const BUILTIN: &[(&str, &str)] = &[
+    ("header.html", include_str!("templates/header.html")),
+    ("footer.html", include_str!("templates/footer.html")),
+    ("grid.html", include_str!("templates/grid.html")),
+    ("mark.html", include_str!("templates/mark.html")),
+    ("number.html", include_str!("templates/number.html")),
+    ("sudoku.html", include_str!("templates/sudoku.html")),
+    ("report.html", include_str!("templates/report.html")),
+    (
+        "sudoku_step.html",
+        include_str!("templates/sudoku_step.html"),
+    ),
+];
+
+/// Puzzle solved with every template when loading them, a few steps from
+/// the end so that it has unsolved cells and steps.
+const SAMPLE: &str =
+    "036129875918375264275846193892654317164937528357218649683491752729563481541782930";
 
 lazy_static! {
     pub static ref TERA: Tera = {
         let mut tera = Tera::default();
-        tera.add_raw_template("header.html", include_str!("templates/header.html"))
-            .unwrap();
-        tera.add_raw_template("footer.html", include_str!("templates/footer.html"))
-            .unwrap();
-        tera.add_raw_template("grid.html", include_str!("templates/grid.html"))
-            .unwrap();
-        tera.add_raw_template("mark.html", include_str!("templates/mark.html"))
-            .unwrap();
-        tera.add_raw_template("number.html", include_str!("templates/number.html"))
-            .unwrap();
-        tera.add_raw_template("sudoku.html", include_str!("templates/sudoku.html"))
-            .unwrap();
-        tera.add_raw_template(
-            "sudoku_step.html",
-            include_str!("templates/sudoku_step.html"),
-        )
-        .unwrap();
+        tera.add_raw_templates(BUILTIN.iter().copied())
+            .expect("built-in templates are valid");
         tera.autoescape_on(vec![]);
         tera
     };
+    static ref BUILTIN_TEMPLATES: Templates = Templates { tera: TERA.clone() };
+}
+
+/// Tera keeps the cause of an error, like an unknown variable, in its
+/// sources.
+fn template_error(e: tera::Error) -> Error {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    Error::new(&message)
+}
+
+/// The templates pages are rendered with.
+#[derive(Clone)]
+pub struct Templates {
+    tera: Tera,
+}
+
+impl Templates {
+    pub fn builtin() -> &'static Templates {
+        &BUILTIN_TEMPLATES
+    }
+
+    /// The .html files of a directory, with the built-in templates for those
+    /// it does not have. Fails if a template does not parse or cannot
+    /// render a sample solve, so errors show before anything is written.
+    pub fn from_dir(dir: &str) -> Result<Templates> {
+        let io_error = |e: std::io::Error| Error::new(&format!("cannot read {}: {}", dir, e));
+        let mut files = vec![];
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.extension().is_some_and(|ext| ext == "html") {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                files.push((name, fs::read_to_string(&path).map_err(io_error)?));
+            }
+        }
+        let mut tera = TERA.clone();
+        tera.add_raw_templates(files).map_err(template_error)?;
+        let templates = Templates { tera };
+        templates.validate()?;
+        Ok(templates)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let renderer =
+            SolverRenderer::new(Solver::new(all_strategies())).with_templates(self.clone());
+        let mut grid = Grid::from_str(SAMPLE)?;
+        renderer.render_pages(&mut grid.clone())?;
+        renderer.solve_and_render_report(&mut grid)?;
+        Ok(())
+    }
+
+    fn render(&self, name: &str, context: &Context) -> Result<String> {
+        self.tera.render(name, context).map_err(template_error)
+    }
 }
 
 pub struct GridRenderer<'a> {
     grid: &'a Grid,
+    delta: Option<&'a StrategyDelta>,
+    templates: &'a Templates,
 }
 
 impl<'a> GridRenderer<'a> {
     pub fn new(grid: &'a Grid) -> Self {
-        Self { grid }
+        Self {
+            grid,
+            delta: None,
+            templates: Templates::builtin(),
+        }
     }
 
-    pub fn tera_context(&self) -> Context {
-        let mut cells: Vec<String> = Vec::new();
-        for cell in self.grid.cells.iter() {
-            cells.push(CellRenderer::new(cell).render());
-        }
+    /// Highlight the changes of the step that led to the grid.
+    pub fn with_delta(mut self, delta: &'a StrategyDelta) -> Self {
+        self.delta = Some(delta);
+        self
+    }
+
+    pub fn with_templates(mut self, templates: &'a Templates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// The cells rendered as HTML, in row order.
+    pub fn cells(&self) -> Result<Vec<String>> {
+        Position::row_vecs()
+            .into_iter()
+            .flatten()
+            .map(|pos| {
+                let cell = self.grid.get_cell(pos);
+                let renderer = CellRenderer::new(&cell).with_templates(self.templates);
+                match self.delta {
+                    Some(delta) => renderer.with_change(delta.change(pos)),
+                    None => renderer,
+                }
+                .render()
+            })
+            .collect()
+    }
+
+    pub fn tera_context(&self) -> Result<Context> {
         let mut context = Context::new();
-        context.insert(
-            "cells",
-            &self
-                .grid
-                .cells
-                .iter()
-                .map(|cell| CellRenderer::new(cell).render())
-                .collect::<Vec<String>>()[0..81],
-        );
-        context
+        context.insert("cells", &self.cells()?);
+        if let Some(delta) = self.delta {
+            context.insert("delta", delta);
+        }
+        Ok(context)
     }
 
-    pub fn render(&self) -> String {
-        TERA.render("sudoku.html", &self.tera_context()).unwrap()
+    pub fn render(&self) -> Result<String> {
+        self.templates.render("sudoku.html", &self.tera_context()?)
     }
 }
 
 pub struct CellRenderer<'a> {
     cell: &'a Cell,
+    change: Option<CellChange>,
+    templates: &'a Templates,
 }
 
 impl<'a> CellRenderer<'a> {
     pub fn new(cell: &'a Cell) -> Self {
-        Self { cell }
+        Self {
+            cell,
+            change: None,
+            templates: Templates::builtin(),
+        }
     }
 
-    pub fn render(&self) -> String {
+    pub fn with_change(mut self, change: CellChange) -> Self {
+        self.change = Some(change);
+        self
+    }
+
+    pub fn with_templates(mut self, templates: &'a Templates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    pub fn render(&self) -> Result<String> {
         let mut context = Context::new();
+        let eliminated = self.change.map_or([false; 9], |c| c.eliminated.0);
+        context.insert("pattern", &self.change.is_some_and(|c| c.pattern));
         match self.cell {
             Cell::Solved(cell) => {
                 let v: u8 = (*cell).into();
                 context.insert("cell", &v);
-                TERA.render("number.html", &context).unwrap()
+                let placed = self.change.is_some_and(|c| c.is_placed(self.cell));
+                context.insert("placed", &placed);
+                self.templates.render("number.html", &context)
             }
             Cell::Unsolved(mcells) => {
                 context.insert("mcells", &mcells.0);
-                TERA.render("mark.html", &context).unwrap()
+                context.insert("eliminated", &eliminated);
+                self.templates.render("mark.html", &context)
             }
         }
     }
@@ -92,17 +230,26 @@ impl<'a> CellRenderer<'a> {
 
 pub struct SolverRenderer {
     solver: Solver,
+    templates: Templates,
 }
 
 impl SolverRenderer {
     pub fn new(solver: Solver) -> Self {
-        Self { solver }
+        Self {
+            solver,
+            templates: Templates::builtin().clone(),
+        }
     }
-    fn strategy_string(strat: Option<Box<dyn Strategy>>) -> String {
+
+    pub fn with_templates(mut self, templates: Templates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Caption of a step, "Start" for the initial grid.
+    pub fn strategy_string(strat: Option<Box<dyn Strategy>>) -> String {
         match strat {
-            Some(strategy) => {
-                format!("[{}] {}", strategy.difficulty(), strategy.name())
-            }
+            Some(strategy) => strategy.label(),
             None => "Start".to_string(),
         }
     }
@@ -110,12 +257,18 @@ impl SolverRenderer {
     fn render_step(
         &self,
         grid: &Grid,
+        delta: Option<&StrategyDelta>,
         step: usize,
         strat: &str,
         link_prev: bool,
         link_next: bool,
-    ) -> String {
-        let mut context = GridRenderer::new(grid).tera_context();
+    ) -> Result<String> {
+        let renderer = GridRenderer::new(grid).with_templates(&self.templates);
+        let mut context = match delta {
+            Some(delta) => renderer.with_delta(delta),
+            None => renderer,
+        }
+        .tera_context()?;
         let link_prev_url = if link_prev {
             format!("step_{:04}.html", (step - 1))
         } else {
@@ -130,48 +283,169 @@ impl SolverRenderer {
         context.insert("strategy", strat);
         context.insert("link_prev", &link_prev_url);
         context.insert("link_next", &link_next_url);
-        TERA.render("sudoku_step.html", &context).unwrap()
+        self.templates.render("sudoku_step.html", &context)
     }
 
-    pub fn solve_and_render(
-        &self,
-        grid: &mut Grid,
-        output_dir: &str,
-    ) -> Result<(), std::io::Error> {
-        let mut step = 0;
-        let mut strat = None;
-        let mut prev_strat = None;
-        let mut prev_grid = *grid;
-        let mut finished = false;
-        loop {
-            if finished {
-                break;
-            }
+    /// Solve the grid and render a page per step.
+    fn render_pages(&self, grid: &mut Grid) -> Result<Vec<String>> {
+        let (mut history, steps) = self.solver.solve_history(grid);
+        let last = history.len();
+        let mut pages = vec![];
+        for step in 0..=last {
+            pages.push(self.render_step(
+                history.grid(),
+                step.checked_sub(1).map(|i| &steps[i].delta),
+                step,
+                history.label(step).unwrap_or("Start"),
+                step != 0,
+                step != last,
+            )?);
+            history.redo();
+        }
+        Ok(pages)
+    }
 
-            match self.solver.solve_step(grid) {
-                Some(step) => {
-                    step.delta.apply(grid);
-                    strat = Some(step.strategy);
-                }
-                None => {
-                    finished = true;
-                }
-            }
-            fs::write(
-                format!("{}/{}", output_dir, format!("step_{:04}.html", step)),
-                self.render_step(
-                    &prev_grid,
-                    step,
-                    &Self::strategy_string(prev_strat),
-                    step != 0,
-                    !finished,
-                ),
-            )?;
-
-            step += 1;
-            prev_strat = strat.clone();
-            prev_grid = *grid;
+    /// Solve the grid and write a page per step in the directory, which is
+    /// created if needed.
+    pub fn solve_and_render(&self, grid: &mut Grid, output_dir: &str) -> Result<()> {
+        let io_error =
+            |e: std::io::Error| Error::new(&format!("cannot write to {}: {}", output_dir, e));
+        let pages = self.render_pages(grid)?;
+        fs::create_dir_all(output_dir).map_err(io_error)?;
+        for (step, page) in pages.iter().enumerate() {
+            fs::write(format!("{}/step_{:04}.html", output_dir, step), page).map_err(io_error)?;
         }
         Ok(())
     }
+
+    /// Solve the grid and render every step in a single page, with a step
+    /// list, strategy descriptions and scripts to move between steps.
+    pub fn solve_and_render_report(&self, grid: &mut Grid) -> Result<String> {
+        let (mut history, steps) = self.solver.solve_history(grid);
+        let mut pages = vec![ReportStep {
+            label: "Start".to_string(),
+            description: "The puzzle as given.",
+            cells: GridRenderer::new(history.grid())
+                .with_templates(&self.templates)
+                .cells()?,
+        }];
+        for (i, step) in steps.iter().enumerate() {
+            history.redo();
+            pages.push(ReportStep {
+                label: history.label(i + 1).unwrap_or_default().to_string(),
+                description: strategy_description(&step.strategy).unwrap_or_default(),
+                cells: GridRenderer::new(history.grid())
+                    .with_templates(&self.templates)
+                    .with_delta(&step.delta)
+                    .cells()?,
+            });
+        }
+        let mut context = Context::new();
+        context.insert("steps", &pages);
+        self.templates.render("report.html", &context)
+    }
+}
+
+/// A step of the single page report, with its cells already rendered.
+#[derive(serde::Serialize)]
+struct ReportStep {
+    label: String,
+    description: &'static str,
+    cells: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+
+    #[test]
+    fn test_cell_change() {
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let step = Solver::new(all_strategies()).solve_step(&grid).unwrap();
+        // The 4 in R1C1 rules out 4 in the rest of row 1
+        let change = CellChange::new(&step.delta, Position::new(1, 1));
+        assert!(change.pattern && !change.placed);
+        let change = CellChange::new(&step.delta, Position::new(2, 1));
+        assert!(change.eliminated.can_be(&4.into()));
+        assert!(!change.pattern);
+    }
+
+    #[test]
+    fn test_render_highlights() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let step = Solver::new(all_strategies()).solve_step(&grid).unwrap();
+        step.delta.apply(&mut grid);
+        let html = GridRenderer::new(&grid)
+            .with_delta(&step.delta)
+            .render()
+            .unwrap();
+        assert!(html.contains(r#"<span class="eliminated">4</span>"#));
+        assert!(html.contains(r#"<p class="number pattern">4</p>"#));
+        let html = GridRenderer::new(&grid).render().unwrap();
+        assert!(!html.contains(r#"class="eliminated""#));
+    }
+
+    #[test]
+    fn test_report_has_every_step() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let steps = Solver::new(all_strategies()).solve(&mut grid.clone()).len();
+        let renderer = SolverRenderer::new(Solver::new(all_strategies()));
+        let html = renderer.solve_and_render_report(&mut grid).unwrap();
+        assert!(grid.is_solved());
+        assert_eq!(html.matches(r#"<section class="step""#).count(), steps + 1);
+        assert!(html.contains(strategy_description("Naked Single").unwrap()));
+    }
+
+    /// A directory of templates for one test, removed when dropped.
+    struct TemplateDir(std::path::PathBuf);
+
+    impl TemplateDir {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "sudoku-templates-{}-{}",
+                name,
+                std::process::id()
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            for (file, content) in files {
+                fs::write(dir.join(file), content).unwrap();
+            }
+            TemplateDir(dir)
+        }
+
+        fn path(&self) -> String {
+            self.0.to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for TemplateDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_templates_from_dir() {
+        let dir = TemplateDir::new("custom", &[("number.html", "<b>{{ cell }}</b>")]);
+        let templates = Templates::from_dir(&dir.path()).unwrap();
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let html = GridRenderer::new(&grid)
+            .with_templates(&templates)
+            .render()
+            .unwrap();
+        assert!(html.contains("<b>4</b>"));
+        // The other templates are the built-in ones
+        assert!(html.contains(r#"<table class="marks">"#));
+    }
+
+    #[test]
+    fn test_bad_templates() {
+        let dir = TemplateDir::new("unclosed", &[("number.html", "{% if cell %}")]);
+        let error = Templates::from_dir(&dir.path()).err().unwrap();
+        assert!(error.to_string().contains("number.html"));
+        let dir = TemplateDir::new("unknown", &[("mark.html", "{{ candidates }}")]);
+        let error = Templates::from_dir(&dir.path()).err().unwrap();
+        assert!(error.to_string().contains("candidates"));
+    }
 }
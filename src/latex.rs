@@ -0,0 +1,218 @@
+//! Printable puzzle booklets as LaTeX source, to be built with pdflatex.
+//!
+//! Puzzles are laid out a set number per page, each with its number and
+//! difficulty label, and their solutions can follow on the last pages.
+
+use std::fmt::Write;
+
+use crate::check::solution;
+use crate::rating::rate;
+use crate::solver::strategies::all_strategies;
+use crate::solver::Solver;
+use crate::{Cell, Grid, Position, Result};
+
+/// Usable page size in cm, A4 with 2cm margins
+const PAGE_WIDTH: f64 = 17.0;
+const PAGE_HEIGHT: f64 = 25.0;
+/// Solutions are printed small, this many per page
+const SOLUTIONS_PER_PAGE: usize = 12;
+
+/// Escape the characters LaTeX treats specially.
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Columns and rows of grids on a page.
+fn layout(per_page: usize) -> (usize, usize) {
+    let columns = match per_page {
+        1 => 1,
+        2..=8 => 2,
+        _ => 3,
+    };
+    (columns, per_page.div_ceil(columns))
+}
+
+pub struct Booklet<'a> {
+    puzzles: &'a [Grid],
+    per_page: usize,
+    solutions: bool,
+    title: Option<String>,
+}
+
+impl<'a> Booklet<'a> {
+    pub fn new(puzzles: &'a [Grid]) -> Self {
+        Self {
+            puzzles,
+            per_page: 4,
+            solutions: false,
+            title: None,
+        }
+    }
+
+    /// Number of puzzles on a page, from 1 to 12.
+    pub fn with_per_page(mut self, per_page: usize) -> Self {
+        self.per_page = per_page.clamp(1, 12);
+        self
+    }
+
+    /// Print the solutions after the puzzles.
+    pub fn with_solutions(mut self, solutions: bool) -> Self {
+        self.solutions = solutions;
+        self
+    }
+
+    /// Title printed at the top of the first page.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// The LaTeX document. Fails if solutions are asked for and a puzzle
+    /// does not have a unique solution.
+    pub fn render(&self) -> Result<String> {
+        let solver = Solver::new(all_strategies());
+        let mut tex = String::new();
+        tex.push_str(concat!(
+            "\\documentclass[a4paper]{article}\n",
+            "\\usepackage[margin=2cm]{geometry}\n",
+            "\\usepackage{tikz}\n",
+            "\\pagestyle{empty}\n",
+            "\\setlength{\\parindent}{0pt}\n",
+            "\\begin{document}\n",
+        ));
+        if let Some(title) = &self.title {
+            writeln!(
+                tex,
+                "\\begin{{center}}\\Huge {}\\end{{center}}",
+                escape(title)
+            )
+            .unwrap();
+        }
+        let captions: Vec<String> = self
+            .puzzles
+            .iter()
+            .enumerate()
+            .map(|(i, puzzle)| {
+                let rating = rate(puzzle, Some(&solver));
+                // The score is only a lower bound for unsolved puzzles
+                if rating.solved {
+                    format!("{}. {} ({:.1})", i + 1, rating.label(), rating.score)
+                } else {
+                    format!("{}. {}", i + 1, rating.label())
+                }
+            })
+            .collect();
+        render_pages(&mut tex, self.puzzles, &captions, self.per_page);
+        if self.solutions {
+            let solutions = self
+                .puzzles
+                .iter()
+                .map(solution)
+                .collect::<Result<Vec<Grid>>>()?;
+            let captions: Vec<String> = (1..=solutions.len()).map(|i| i.to_string()).collect();
+            tex.push_str("\\newpage\n\\begin{center}\\Large Solutions\\end{center}\n");
+            render_pages(&mut tex, &solutions, &captions, SOLUTIONS_PER_PAGE);
+        }
+        tex.push_str("\\end{document}\n");
+        Ok(tex)
+    }
+}
+
+/// Grids with their captions, per_page on each page.
+fn render_pages(tex: &mut String, grids: &[Grid], captions: &[String], per_page: usize) {
+    let (columns, rows) = layout(per_page);
+    // Room for the caption and the gaps between grids
+    let size = (PAGE_WIDTH / columns as f64 - 1.0).min(PAGE_HEIGHT / rows as f64 - 2.0);
+    for (i, (grid, caption)) in grids.iter().zip(captions).enumerate() {
+        if i > 0 && i % per_page == 0 {
+            tex.push_str("\\newpage\n");
+        } else if i > 0 && i % columns == 0 {
+            tex.push_str("\\vfill\n");
+        }
+        writeln!(
+            tex,
+            "\\begin{{minipage}}{{{:.2}\\textwidth}}\\centering",
+            1.0 / columns as f64 - 0.02
+        )
+        .unwrap();
+        render_grid(tex, grid, size);
+        writeln!(
+            tex,
+            "\\\\[0.3em]{}\n\\end{{minipage}}\\hfill",
+            escape(caption)
+        )
+        .unwrap();
+    }
+    tex.push_str("\\vfill\n");
+}
+
+/// A grid as a TikZ picture, size cm wide.
+fn render_grid(tex: &mut String, grid: &Grid, size: f64) {
+    let cell = size / 9.0;
+    writeln!(tex, "\\begin{{tikzpicture}}[x={0:.3}cm,y={0:.3}cm]", cell).unwrap();
+    tex.push_str("\\draw[gray] (0,0) grid (9,9);\n");
+    tex.push_str("\\draw[very thick,step=3] (0,0) grid (9,9);\n");
+    // Digits at about two thirds of the cell height, in points
+    let font = cell * 28.35 * 0.6;
+    for pos in Position::row_vecs().into_iter().flatten() {
+        if let Cell::Solved(v) = grid.get_cell(pos) {
+            writeln!(
+                tex,
+                "\\node at ({}.5,{}.5) {{\\fontsize{{{:.1}}}{{{:.1}}}\\selectfont {}}};",
+                pos.col() - 1,
+                9 - pos.row(),
+                font,
+                font,
+                u8::from(v)
+            )
+            .unwrap();
+        }
+    }
+    tex.push_str("\\end{tikzpicture}\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PUZZLE;
+
+    #[test]
+    fn test_booklet() {
+        let puzzles = vec![Grid::from_str(PUZZLE).unwrap(); 2];
+        let tex = Booklet::new(&puzzles)
+            .with_per_page(1)
+            .with_solutions(true)
+            .with_title("Week 1 & 2")
+            .render()
+            .unwrap();
+        assert!(tex.contains("Week 1 \\& 2"));
+        assert_eq!(tex.matches("\\begin{tikzpicture}").count(), 4);
+        // One page break in the puzzles, then the solutions page
+        assert_eq!(tex.matches("\\newpage").count(), 2);
+        assert!(tex.contains("2. Easy (1.2)"));
+        assert!(tex.ends_with("\\end{document}\n"));
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(layout(1), (1, 1));
+        assert_eq!(layout(4), (2, 2));
+        assert_eq!(layout(6), (2, 3));
+        assert_eq!(layout(12), (3, 4));
+    }
+}
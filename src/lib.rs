@@ -5,11 +5,34 @@ use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+pub mod canonical;
+pub mod check;
+pub mod encoding;
+#[cfg(test)]
+mod fixtures;
+pub mod game;
+pub mod generator;
+pub mod graph;
+pub mod hint;
+pub mod history;
 #[cfg(feature = "html")]
 pub mod html;
+pub mod latex;
+pub mod parse;
+pub mod pencilmark;
+pub mod puzzle;
+pub mod rating;
+pub mod report;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod solver;
 pub mod stats;
+pub mod svg;
+pub mod terminal;
 pub mod threads;
+pub mod transform;
+
+use parse::parse_grid;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct CellValue(u8);
@@ -110,6 +133,11 @@ impl fmt::Display for Candidates {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Cell {
     Solved(CellValue),
     Unsolved(Candidates),
@@ -234,16 +262,16 @@ impl Position {
         let s: HashSet<Position> = self
             .col_vec(include_self)
             .into_iter()
-            .chain(self.row_vec(include_self).into_iter())
-            .chain(self.box_vec(include_self).into_iter())
+            .chain(self.row_vec(include_self))
+            .chain(self.box_vec(include_self))
             .collect();
         s.into_iter().collect()
     }
 
-    /// All positions in a grid
+    /// All positions in a grid, in row order
     pub fn grid_vec() -> Vec<Self> {
         (1..=9)
-            .flat_map(|col| (1..9).map(move |row| Position::new(col, row)))
+            .flat_map(|row| (1..=9).map(move |col| Position::new(col, row)))
             .collect()
     }
 
@@ -329,18 +357,9 @@ impl Grid {
 impl FromStr for Grid {
     type Err = Error;
 
+    /// See parse::parse_grid for the accepted formats.
     fn from_str(s: &str) -> Result<Grid> {
-        if s.len() != 81 {
-            return Err(Error::new("Puzzle string must have 81 characters"));
-        }
-        Ok(Grid {
-            cells: s
-                .chars()
-                .map(|digit| Cell::from_str(&digit.to_string()).unwrap())
-                .collect::<Vec<Cell>>()[0..81]
-                .try_into()
-                .unwrap(),
-        })
+        Ok(parse_grid(s)?)
     }
 }
 
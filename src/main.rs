@@ -1,60 +1,85 @@
-use std::str::FromStr;
-use std::time::Instant;
+use std::ffi::OsString;
 
 use structopt::StructOpt;
 
-#[cfg(feature = "html")]
-use sudoku::html::SolverRenderer;
+mod cmd;
 
-use sudoku::solver::{strategies::all_strategies, Solver};
-use sudoku::Grid;
+use cmd::{
+    bench::BenchCmd, booklet::BookletCmd, count::CountCmd, dedupe::DedupeCmd,
+    generate::GenerateCmd, hint::HintCmd, rate::RateCmd, render::RenderCmd, solve::SolveCmd,
+    transform::TransformCmd, validate::ValidateCmd,
+};
 
 #[derive(Debug, StructOpt)]
-struct Cli {
-    #[cfg(feature = "html")]
-    #[structopt(long = "output-html", short = "o")]
-    html_output_dir: Option<String>,
-    puzzle: String,
+#[structopt(
+    name = "sudokusolver",
+    about = "Solve, rate and generate sudoku puzzles"
+)]
+enum Cli {
+    Solve(SolveCmd),
+    Hint(HintCmd),
+    Rate(RateCmd),
+    Validate(ValidateCmd),
+    Count(CountCmd),
+    Generate(GenerateCmd),
+    Transform(TransformCmd),
+    Render(RenderCmd),
+    Booklet(BookletCmd),
+    Dedupe(DedupeCmd),
+    Bench(BenchCmd),
 }
 
-fn solve_and_print(solver: Solver, mut sudoku: Grid) {
-    let start = Instant::now();
-    let steps = solver.solve(&mut sudoku);
-    for (i, step) in steps.iter().enumerate() {
-        eprintln!(
-            "{} {} ({} μs) : {} ",
-            i,
-            &step.strategy.name(),
-            step.time.as_micros(),
-            step.delta,
-        );
-    }
-    eprintln!("Total time: {} ms", start.elapsed().as_millis());
-    println!("{}", sudoku);
-}
+const SUBCOMMANDS: &[&str] = &[
+    "solve",
+    "hint",
+    "rate",
+    "validate",
+    "count",
+    "generate",
+    "transform",
+    "render",
+    "booklet",
+    "dedupe",
+    "bench",
+    "help",
+    "-h",
+    "--help",
+    "-V",
+    "--version",
+];
 
-#[cfg(feature = "html")]
-fn solve_and_render_html(solver: Solver, mut sudoku: Grid, out_dir: &str) {
-    let renderer = SolverRenderer::new(solver);
-    renderer.solve_and_render(&mut sudoku, out_dir).unwrap();
-    println!("{}", sudoku.to_string());
+/// Command line arguments, with "solve" inserted when no subcommand is given
+/// so that the old `sudokusolver [-o DIR] PUZZLE` invocation still works.
+fn args() -> Vec<OsString> {
+    let mut args: Vec<OsString> = std::env::args_os().collect();
+    if let Some(first) = args.get(1) {
+        if !SUBCOMMANDS.iter().any(|s| first == s) {
+            args.insert(1, "solve".into());
+        }
+    }
+    args
 }
 
 fn main() {
-    let args = Cli::from_args();
-    let sudoku = Grid::from_str(&args.puzzle).unwrap();
-    let solver = Solver::new(all_strategies());
-
-    #[cfg(feature = "html")]
-    match args.html_output_dir {
-        Some(dir) => {
-            solve_and_render_html(solver, sudoku, &dir);
-        }
-        None => {
-            solve_and_print(solver, sudoku);
+    let result = match Cli::from_iter(args()) {
+        Cli::Solve(cmd) => cmd.run(),
+        Cli::Hint(cmd) => cmd.run(),
+        Cli::Rate(cmd) => cmd.run(),
+        Cli::Validate(cmd) => cmd.run(),
+        Cli::Count(cmd) => cmd.run(),
+        Cli::Generate(cmd) => cmd.run(),
+        Cli::Transform(cmd) => cmd.run(),
+        Cli::Render(cmd) => cmd.run(),
+        Cli::Booklet(cmd) => cmd.run(),
+        Cli::Dedupe(cmd) => cmd.run(),
+        Cli::Bench(cmd) => cmd.run(),
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
     }
-
-    #[cfg(not(feature = "html"))]
-    solve_and_print(&args.puzzle);
 }
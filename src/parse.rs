@@ -0,0 +1,216 @@
+use std::fmt;
+
+use crate::{Cell, Error, Grid, Result};
+
+/// Error for a malformed puzzle string, with the 1-based line and column
+/// of the offending character.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    details: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, msg: &str) -> Self {
+        Self {
+            line,
+            column,
+            details: msg.to_string(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.details
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::new(&e.to_string())
+    }
+}
+
+const BLANKS: &[char] = &['.', '0', '_', '*', '-'];
+const SEPARATORS: &[char] = &['|', '+', ',', ';'];
+
+/// Lines made only of box borders, like "------+-------+------" or "=====".
+/// A line of exactly 9 dashes is a row of blank cells, not a border.
+fn is_border(line: &str) -> bool {
+    let mut dashes = 0;
+    let mut other = false;
+    for c in line.chars() {
+        match c {
+            '-' => dashes += 1,
+            '+' | '=' => other = true,
+            '|' => {}
+            c if c.is_whitespace() => {}
+            _ => return false,
+        }
+    }
+    other || dashes > 9
+}
+
+/// Parse a puzzle in any of the common text formats.
+///
+/// Digits 1-9 are clues, and any of `.`, `0`, `_`, `*` or `-` is a blank cell.
+/// Whitespace and `|`, `+`, `,`, `;` separators are ignored, as are border
+/// lines of boxed grids, so both 81-character strings and multi-line grids
+/// copied from forums are accepted. Cells are read in row order.
+pub fn parse_grid(s: &str) -> std::result::Result<Grid, ParseError> {
+    let mut cells = vec![];
+    let mut last = (1, 1);
+    for (i, line) in s.lines().enumerate() {
+        if is_border(line) {
+            continue;
+        }
+        for (j, c) in line.chars().enumerate() {
+            let (line_no, column) = (i + 1, j + 1);
+            let cell = if let Some(n) = c.to_digit(10).filter(|n| *n > 0) {
+                Cell::from_u8(n as u8)
+            } else if BLANKS.contains(&c) {
+                Cell::from_u8(0)
+            } else if c.is_whitespace() || SEPARATORS.contains(&c) {
+                continue;
+            } else {
+                return Err(ParseError::new(
+                    line_no,
+                    column,
+                    &format!("unexpected character '{}'", c),
+                ));
+            };
+            if cells.len() == 81 {
+                return Err(ParseError::new(
+                    line_no,
+                    column,
+                    "puzzle has more than 81 cells",
+                ));
+            }
+            cells.push(cell);
+            last = (line_no, column);
+        }
+    }
+    if cells.len() < 81 {
+        return Err(ParseError::new(
+            last.0,
+            last.1,
+            &format!("puzzle has {} cells, expected 81", cells.len()),
+        ));
+    }
+    let mut grid = Grid::new();
+    grid.cells.copy_from_slice(&cells);
+    Ok(grid)
+}
+
+/// Parse a puzzle, or a solving state with candidates: a pencilmark grid,
+/// a 729-character candidate string or a base64 candidate grid.
+/// Errors are reported as for a puzzle.
+pub fn parse_state(s: &str) -> Result<Grid> {
+    let trimmed = s.trim();
+    match trimmed.chars().count() {
+        729 => return Grid::from_candidate_string(trimmed),
+        136 if !trimmed.contains(char::is_whitespace) => return Grid::from_base64(trimmed),
+        _ => {}
+    }
+    match parse_grid(s) {
+        Ok(grid) => Ok(grid),
+        Err(e) => Grid::from_pencilmarks(s).or(Err(e.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::fixtures::PUZZLE;
+
+    #[test_case(PUZZLE; "digits")]
+    #[test_case(
+        "...12....9....5.6..75.4..93.92.5...7164.3.5283...1.64.68..9.75..2.5....1....82...";
+        "dots"
+    )]
+    #[test_case(
+        "___12____9____5_6__75*4**93-92-5---7164.3.528 300010640680090750020500001000082000";
+        "mixed blanks and whitespace"
+    )]
+    #[test_case(
+        "000 120 000\n900 005 060\n075 040 093\n092 050 007\n164 030 528\n\
+         300 010 640\n680 090 750\n020 500 001\n000 082 000\n";
+        "lines"
+    )]
+    #[test_case(
+        "+-------+-------+-------+\n\
+         | . . . | 1 2 . | . . . |\n\
+         | 9 . . | . . 5 | . 6 . |\n\
+         | . 7 5 | . 4 . | . 9 3 |\n\
+         +-------+-------+-------+\n\
+         | . 9 2 | . 5 . | . . 7 |\n\
+         | 1 6 4 | . 3 . | 5 2 8 |\n\
+         | 3 . . | . 1 . | 6 4 . |\n\
+         +-------+-------+-------+\n\
+         | 6 8 . | . 9 . | 7 5 . |\n\
+         | . 2 . | 5 . . | . . 1 |\n\
+         | . . . | . 8 2 | . . . |\n\
+         +-------+-------+-------+\n";
+        "boxed"
+    )]
+    #[test_case(
+        "---|12-|---\n9--|--5|-6-\n-75|-4-|-93\n------+-------+------\n\
+         -92|-5-|--7\n164|-3-|528\n3--|-1-|64-\n=====\n\
+         68-|-9-|75-\n-2-|5--|--1\n---|-82|---\n";
+        "dashes"
+    )]
+    fn test_parse_formats(s: &str) {
+        assert_eq!(parse_grid(s).unwrap().to_string(), PUZZLE);
+    }
+
+    #[test]
+    fn test_unexpected_character() {
+        let s = "000 120 000\n900 0x5 060\n";
+        let err = parse_grid(s).unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 6));
+    }
+
+    #[test]
+    fn test_too_many_cells() {
+        let err = parse_grid(&format!("{}12", PUZZLE)).unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 82));
+    }
+
+    #[test]
+    fn test_parse_state() {
+        let mut grid = parse_grid(PUZZLE).unwrap();
+        grid.cells[0] = Cell::Unsolved(crate::Candidates::new([
+            false, true, true, false, false, false, false, true, false,
+        ]));
+        assert_eq!(parse_state(&grid.to_pencilmarks()).unwrap(), grid);
+        assert_eq!(parse_state(&grid.to_candidate_string()).unwrap(), grid);
+        assert_eq!(parse_state(&grid.to_base64()).unwrap(), grid);
+        assert_eq!(parse_state(PUZZLE).unwrap(), parse_grid(PUZZLE).unwrap());
+        assert!(parse_state("000 120 0x0").is_err());
+    }
+
+    #[test]
+    fn test_too_few_cells() {
+        let err = parse_grid(&PUZZLE[..80]).unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 80));
+        assert!(parse_grid("").is_err());
+    }
+}
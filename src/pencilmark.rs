@@ -0,0 +1,250 @@
+use crate::parse::ParseError;
+use crate::{Candidates, Cell, CellValue, Grid};
+
+/// Text for a cell in a pencilmark grid: the value of a solved cell, or the
+/// candidates of an unsolved cell. HoDoKu has no token for the two states
+/// below, so these extend its format: a cell with no candidates left is
+/// "0", and an unsolved cell with a single candidate is marked with a '?',
+/// like "5?", to tell it from a solved cell.
+fn cell_token(cell: &Cell) -> String {
+    match cell {
+        Cell::Solved(v) => u8::from(*v).to_string(),
+        Cell::Unsolved(candidates) => {
+            let s: String = candidates
+                .to_vec()
+                .into_iter()
+                .map(|v| u8::from(v).to_string())
+                .collect();
+            match s.len() {
+                0 => "0".to_string(),
+                1 => format!("{}?", s),
+                _ => s,
+            }
+        }
+    }
+}
+
+fn parse_token(token: &str, line: usize, column: usize) -> Result<Cell, ParseError> {
+    if token == "0" {
+        return Ok(Cell::Unsolved(Candidates::new([false; 9])));
+    }
+    let (digits, single) = match token.strip_suffix('?') {
+        Some(digits) => (digits, true),
+        None => (token, false),
+    };
+    let mut candidates = Candidates::new([false; 9]);
+    for (k, c) in digits.chars().enumerate() {
+        match c.to_digit(10).filter(|n| *n > 0) {
+            Some(n) if candidates.can_be(&CellValue::new(n as u8)) => {
+                return Err(ParseError::new(
+                    line,
+                    column + k,
+                    &format!("repeated candidate '{}'", c),
+                ))
+            }
+            Some(n) => candidates.add(&CellValue::new(n as u8)),
+            None => {
+                return Err(ParseError::new(
+                    line,
+                    column + k,
+                    &format!("unexpected character '{}'", c),
+                ))
+            }
+        }
+    }
+    match (digits.chars().count(), single) {
+        (1, false) => Ok(Cell::Solved(candidates.to_vec()[0])),
+        (1, true) => Ok(Cell::Unsolved(candidates)),
+        (_, true) => Err(ParseError::new(
+            line,
+            column,
+            &format!("'?' marks a single candidate, not '{}'", token),
+        )),
+        _ => Ok(Cell::Unsolved(candidates)),
+    }
+}
+
+/// Lines like ".-----.-----.", ":-----+-----:" or "'-----'-----'".
+fn is_border(line: &str) -> bool {
+    line.contains('-')
+        && line
+            .chars()
+            .all(|c| c.is_whitespace() || ".:'+-|=".contains(c))
+}
+
+impl Grid {
+    /// Pencilmark grid as used by HoDoKu and on forums: one line per row,
+    /// solved cells as a single digit and unsolved cells as the string of
+    /// their candidates, with box borders.
+    ///
+    /// The output is an extension of that format, so that any state reads
+    /// back unchanged: a single candidate left in an unsolved cell is
+    /// followed by '?', and a cell without candidates is "0". Other tools
+    /// may not read grids holding these, which only come up mid-solve or
+    /// in broken states.
+    pub fn to_pencilmarks(&self) -> String {
+        let tokens: Vec<String> = self.cells.iter().map(cell_token).collect();
+        let widths: Vec<usize> = (0..9)
+            .map(|c| (0..9).map(|r| tokens[r * 9 + c].len()).max().unwrap())
+            .collect();
+        let border = |left: char, middle: char, right: char| {
+            let segments: Vec<String> = (0..3)
+                .map(|stack| {
+                    let w: usize = widths[stack * 3..stack * 3 + 3].iter().sum();
+                    "-".repeat(w + 6)
+                })
+                .collect();
+            format!("{}{}{}\n", left, segments.join(&middle.to_string()), right)
+        };
+        let mut out = border('.', '.', '.');
+        for row in 0..9 {
+            if row == 3 || row == 6 {
+                out.push_str(&border(':', '+', ':'));
+            }
+            let segments: Vec<String> = (0..3)
+                .map(|stack| {
+                    let cells: Vec<String> = (stack * 3..stack * 3 + 3)
+                        .map(|c| format!("{:w$}", tokens[row * 9 + c], w = widths[c]))
+                        .collect();
+                    format!(" {} ", cells.join("  "))
+                })
+                .collect();
+            out.push_str(&format!("|{}|\n", segments.join("|")));
+        }
+        out.push_str(&border('\'', '\'', '\''));
+        out
+    }
+
+    /// Read a pencilmark grid, see to_pencilmarks. Cells are whitespace or
+    /// '|' separated and read in row order, border lines are ignored.
+    /// A single digit is a solved cell, several digits, or one followed by
+    /// '?', are candidates, and "0" is a cell without candidates. A digit
+    /// repeated in a cell is an error.
+    pub fn from_pencilmarks(s: &str) -> Result<Grid, ParseError> {
+        let mut grid = Grid::new();
+        let mut count = 0;
+        let mut last = (1, 1);
+        for (i, line) in s.lines().enumerate() {
+            if is_border(line) {
+                continue;
+            }
+            let chars: Vec<char> = line.chars().collect();
+            let mut j = 0;
+            while j < chars.len() {
+                if chars[j].is_whitespace() || chars[j] == '|' {
+                    j += 1;
+                    continue;
+                }
+                let start = j;
+                while j < chars.len() && !chars[j].is_whitespace() && chars[j] != '|' {
+                    j += 1;
+                }
+                let token: String = chars[start..j].iter().collect();
+                if count == 81 {
+                    return Err(ParseError::new(
+                        i + 1,
+                        start + 1,
+                        "pencilmark grid has more than 81 cells",
+                    ));
+                }
+                grid.cells[count] = parse_token(&token, i + 1, start + 1)?;
+                count += 1;
+                last = (i + 1, start + 1);
+            }
+        }
+        if count < 81 {
+            return Err(ParseError::new(
+                last.0,
+                last.1,
+                &format!("pencilmark grid has {} cells, expected 81", count),
+            ));
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::Solver;
+    use crate::Position;
+
+    const HODOKU_TOP: &str = "\
+.----------------.----------------.----------------.
+| 5    37   1    | 2   34   9     | 6   8    47   |
+";
+
+    #[test]
+    fn test_round_trip_partial_solve() {
+        let solver = Solver::new(all_strategies());
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        for _ in 0..5 {
+            solver.solve_step(&grid).unwrap().delta.apply(&mut grid);
+        }
+        let text = grid.to_pencilmarks();
+        assert_eq!(Grid::from_pencilmarks(&text).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_round_trip_no_candidates() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        grid.cells[1] = Cell::Unsolved(Candidates::new([false; 9]));
+        let text = grid.to_pencilmarks();
+        assert_eq!(Grid::from_pencilmarks(&text).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_round_trip_single_candidate() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let mut single = [false; 9];
+        single[4] = true;
+        grid.cells[1] = Cell::Unsolved(Candidates::new(single));
+        let text = grid.to_pencilmarks();
+        assert!(text.contains(" 5? "));
+        let parsed = Grid::from_pencilmarks(&text).unwrap();
+        assert_eq!(parsed, grid);
+        assert_eq!(parsed.cells[0], Cell::Solved(4.into()));
+    }
+
+    #[test]
+    fn test_bad_single_candidate() {
+        let err = Grid::from_pencilmarks("| 12? |").unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 3));
+        assert!(Grid::from_pencilmarks("| ? |").is_err());
+    }
+
+    #[test]
+    fn test_repeated_candidate() {
+        let err = Grid::from_pencilmarks("| 12 33 |").unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 7));
+    }
+
+    #[test]
+    fn test_read_hodoku_row() {
+        let mut text = HODOKU_TOP.to_string();
+        for row in 0..8 {
+            if row == 2 || row == 5 {
+                text.push_str(":-------+-------+-------:\n");
+            }
+            text.push_str("| 123456789 1 2 | 3 4 5 | 6 7 8 |\n");
+        }
+        text.push_str("'-------'-------'-------'\n");
+        let grid = Grid::from_pencilmarks(&text).unwrap();
+        assert_eq!(grid.get_cell(Position::new(1, 1)), Cell::Solved(5.into()));
+        assert_eq!(
+            grid.get_cell(Position::new(2, 1)).candidates(),
+            Some(vec![3.into(), 7.into()])
+        );
+        assert_eq!(grid.get_cell(Position::new(9, 9)), Cell::Solved(8.into()));
+    }
+
+    #[test]
+    fn test_bad_token_position() {
+        let err = Grid::from_pencilmarks("| 12 3x4 |").unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 7));
+    }
+}
@@ -0,0 +1,276 @@
+use std::io::{self, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+use structopt::StructOpt;
+
+use sudoku::game::Game;
+use sudoku::generator::Generator;
+use sudoku::parse::parse_state;
+use sudoku::{Cell, CellValue, Grid, Position};
+
+const HELP: &str = "arrows move  1-9 enter  p pencil  space clear  c candidates  \
+                    h hint  a apply hint  u undo  r redo  q quit";
+
+#[derive(Debug, StructOpt)]
+struct Cli {
+    /// Seed for the generated puzzle when none is given
+    #[structopt(long)]
+    seed: Option<u64>,
+    /// The puzzle, as 81 digits or a multi-line grid, or a solving state
+    /// as a pencilmark grid or candidate string. A new puzzle is generated
+    /// if omitted.
+    puzzle: Option<String>,
+}
+
+/// Text of one of the three lines of a cell: a third of the pencilmarks,
+/// or the value on the middle line.
+fn cell_line(cell: &Cell, line: u8) -> String {
+    match cell {
+        Cell::Solved(v) if line == 1 => format!(" {} ", u8::from(*v)),
+        Cell::Solved(_) => "   ".to_string(),
+        Cell::Unsolved(c) if c.count() == 9 => "   ".to_string(),
+        Cell::Unsolved(c) => (1..=3)
+            .map(|i| {
+                let n = line * 3 + i;
+                if c.can_be(&CellValue::new(n)) {
+                    char::from(b'0' + n)
+                } else {
+                    '·'
+                }
+            })
+            .collect(),
+    }
+}
+
+fn draw(out: &mut impl Write, game: &Game, message: &str) -> io::Result<()> {
+    let conflicts = game.conflicts();
+    let hint = game.current_hint();
+    let region = hint
+        .as_ref()
+        .and_then(|h| h.region)
+        .map(|r| r.positions())
+        .unwrap_or_default();
+    let changed: Vec<Position> = hint
+        .as_ref()
+        .and_then(|h| h.delta.as_ref())
+        .map(|d| {
+            let solved = d.solved().into_iter().map(|(p, _)| p);
+            solved
+                .chain(d.eliminated().into_iter().map(|(p, _)| p))
+                .collect()
+        })
+        .unwrap_or_default();
+    let border = "+-------------+-------------+-------------+";
+
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+    let mut y = 0;
+    for row in 1..=9u8 {
+        if row % 3 == 1 {
+            queue!(out, MoveTo(0, y), Print(border))?;
+            y += 1;
+        }
+        for line in 0..3 {
+            queue!(out, MoveTo(0, y), Print("|"))?;
+            for col in 1..=9u8 {
+                let pos = Position::new(col, row);
+                let color = if conflicts.contains(&pos) {
+                    Color::Red
+                } else if changed.contains(&pos) {
+                    Color::Green
+                } else if region.contains(&pos) {
+                    Color::Yellow
+                } else if game.is_given(pos) {
+                    Color::White
+                } else {
+                    Color::Cyan
+                };
+                queue!(out, Print(" "), SetForegroundColor(color))?;
+                if game.is_given(pos) {
+                    queue!(out, SetAttribute(Attribute::Bold))?;
+                }
+                if pos == game.cursor() {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(
+                    out,
+                    Print(cell_line(&game.grid().get_cell(pos), line)),
+                    SetAttribute(Attribute::Reset),
+                    ResetColor
+                )?;
+                if col % 3 == 0 {
+                    queue!(out, Print(" |"))?;
+                }
+            }
+            y += 1;
+        }
+    }
+    let mode = if game.is_pencil() { "pencil" } else { "value" };
+    queue!(
+        out,
+        MoveTo(0, y),
+        Print(border),
+        MoveTo(0, y + 1),
+        Print(format!(
+            "{} {} mode  step {}  {}",
+            game.cursor(),
+            mode,
+            game.history().step(),
+            message
+        )),
+        MoveTo(0, y + 2),
+        Print(HELP)
+    )?;
+    out.flush()
+}
+
+/// Handle a key press, returning the message to show, or None to quit.
+fn handle_key(game: &mut Game, key: KeyEvent) -> Option<String> {
+    let cursor = game.cursor();
+    let message = match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => return None,
+        KeyCode::Left => {
+            game.move_cursor(-1, 0);
+            String::new()
+        }
+        KeyCode::Right => {
+            game.move_cursor(1, 0);
+            String::new()
+        }
+        KeyCode::Up => {
+            game.move_cursor(0, -1);
+            String::new()
+        }
+        KeyCode::Down => {
+            game.move_cursor(0, 1);
+            String::new()
+        }
+        KeyCode::Char(c @ '1'..='9') => {
+            game.input(CellValue::new(c as u8 - b'0'));
+            String::new()
+        }
+        KeyCode::Char(' ') | KeyCode::Char('0') | KeyCode::Backspace | KeyCode::Delete => {
+            game.clear(cursor);
+            String::new()
+        }
+        KeyCode::Char('p') => {
+            game.toggle_pencil();
+            String::new()
+        }
+        KeyCode::Char('c') => {
+            game.autofill();
+            "candidates filled".to_string()
+        }
+        KeyCode::Char('u') => {
+            if game.undo() {
+                String::new()
+            } else {
+                "nothing to undo".to_string()
+            }
+        }
+        KeyCode::Char('r') => {
+            if game.redo() {
+                String::new()
+            } else {
+                "nothing to redo".to_string()
+            }
+        }
+        KeyCode::Char('h') => match game.hint() {
+            Some(hint) => format!("hint: {}", hint),
+            None => "no strategy applies".to_string(),
+        },
+        KeyCode::Char('a') => {
+            if game.apply_hint() {
+                "hint applied".to_string()
+            } else {
+                "ask for a hint first".to_string()
+            }
+        }
+        _ => String::new(),
+    };
+    if game.is_solved() {
+        return Some("solved!".to_string());
+    }
+    Some(message)
+}
+
+fn play(puzzle: Grid) -> io::Result<()> {
+    let mut game = Game::new(puzzle);
+    let mut out = io::stdout();
+    let mut message = String::new();
+    loop {
+        draw(&mut out, &game, &message)?;
+        if let Event::Key(key) = read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match handle_key(&mut game, key) {
+                Some(m) => message = m,
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Raw mode and the alternate screen, left when dropped so that the
+/// terminal is usable again after an error or a panic.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(guard)
+    }
+
+    fn restore() {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let puzzle = match &args.puzzle {
+        Some(s) => parse_state(s),
+        None => {
+            let mut generator = Generator::new();
+            if let Some(seed) = args.seed {
+                generator = generator.with_seed(seed);
+            }
+            generator.generate()
+        }
+    };
+    let puzzle = match puzzle {
+        Ok(puzzle) => puzzle,
+        Err(e) => {
+            eprintln!("invalid puzzle: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Restore the terminal before a panic message is printed, or it would
+    // be lost with the alternate screen
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        TerminalGuard::restore();
+        hook(info);
+    }));
+    let result = TerminalGuard::new().and_then(|_terminal| play(puzzle));
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,57 @@
+use std::convert::TryInto;
+use std::str::FromStr;
+
+use crate::{Error, Grid, Result};
+
+/// A puzzle from a puzzle collection, stored one per line as
+/// `<id> <puzzle> <rating>`, like the files in the puzzles directory.
+pub struct Puzzle {
+    pub id: String,
+    pub grid: Grid,
+    pub rating: f64,
+}
+
+impl FromStr for Puzzle {
+    type Err = Error;
+    fn from_str(s: &str) -> std::result::Result<Self, <Self as FromStr>::Err> {
+        let [id, puzzle_str, rating]: [&str; 3] = s
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .try_into()
+            .map_err(|_| Error::new("expected '<id> <puzzle> <rating>'"))?;
+        Ok(Puzzle {
+            id: id.to_string(),
+            grid: Grid::from_str(puzzle_str)?,
+            rating: rating
+                .parse()
+                .map_err(|_| Error::new(&format!("Invalid rating: {}", rating)))?,
+        })
+    }
+}
+
+/// The puzzles of a collection, skipping blank lines and '#' comments.
+/// Errors give the line number of the first malformed line.
+pub fn parse_puzzles(s: &str) -> Result<Vec<Puzzle>> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            Puzzle::from_str(line).map_err(|e| Error::new(&format!("line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_puzzles() {
+        let puzzles = parse_puzzles(include_str!("../puzzles/benchmark.txt")).unwrap();
+        assert_eq!(puzzles.len(), 1000);
+        let err = parse_puzzles("# comment\n\nabc 123 4.5\n").err().unwrap();
+        assert!(err.to_string().starts_with("line 3: "));
+        let err = parse_puzzles("abc\n").err().unwrap();
+        assert_eq!(err.to_string(), "line 1: expected '<id> <puzzle> <rating>'");
+    }
+}
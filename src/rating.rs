@@ -0,0 +1,122 @@
+//! Puzzle difficulty ratings, on a scale close to the Sudoku Explainer one
+//! used for the ratings in the puzzles directory.
+//!
+//! The rating is the score of the hardest strategy the solver needed. The
+//! solver's Naked Single also removes solved values from their peers, which
+//! every other strategy relies on, so it scores 1.2 like Hidden Single, the
+//! lowest Sudoku Explainer rating, rather than 2.3.
+
+use crate::solver::strategies::all_strategies;
+use crate::solver::Solver;
+use crate::Grid;
+
+/// Score of a strategy, or None for a strategy without a known score.
+pub fn strategy_score(name: &str) -> Option<f64> {
+    match name {
+        "Naked Single" => Some(1.2),
+        "Hidden Single" => Some(1.2),
+        "Naked Pair" => Some(3.0),
+        "Hidden Pair" => Some(3.4),
+        "Naked Triple" => Some(3.6),
+        "Hidden Triple" => Some(4.0),
+        "Naked Quad" => Some(5.0),
+        "Hidden Quad" => Some(5.4),
+        _ => None,
+    }
+}
+
+/// Score used for strategies missing from strategy_score.
+const UNKNOWN_SCORE: f64 = 10.0;
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rating {
+    /// Score of the hardest strategy used
+    pub score: f64,
+    /// Name of the hardest strategy used, None if the puzzle was already solved
+    pub hardest: Option<String>,
+    pub steps: usize,
+    /// False if the strategies could not finish the puzzle, in which case
+    /// the score is only a lower bound
+    pub solved: bool,
+}
+
+impl Rating {
+    /// Difficulty label for puzzle sheets: Easy for singles only, then
+    /// Medium for pairs, Hard for triples and Expert beyond. Puzzles the
+    /// strategies cannot finish are Extreme.
+    pub fn label(&self) -> &'static str {
+        if !self.solved {
+            "Extreme"
+        } else if self.score < 2.0 {
+            "Easy"
+        } else if self.score < 3.5 {
+            "Medium"
+        } else if self.score < 4.5 {
+            "Hard"
+        } else {
+            "Expert"
+        }
+    }
+}
+
+/// Rate a puzzle by solving it with the given solver, or with all strategies.
+pub fn rate(grid: &Grid, solver: Option<&Solver>) -> Rating {
+    let default_solver;
+    let solver = match solver {
+        Some(solver) => solver,
+        None => {
+            default_solver = Solver::new(all_strategies());
+            &default_solver
+        }
+    };
+    let mut grid = *grid;
+    let mut rating = Rating {
+        score: 0.0,
+        hardest: None,
+        steps: 0,
+        solved: false,
+    };
+    for step in solver.solve(&mut grid) {
+        let name = step.strategy.name();
+        let score = strategy_score(&name).unwrap_or(UNKNOWN_SCORE);
+        if rating.hardest.is_none() || score > rating.score {
+            rating.score = score;
+            rating.hardest = Some(name);
+        }
+        rating.steps += 1;
+    }
+    rating.solved = grid.is_solved();
+    rating
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_rate_matches_benchmark() {
+        let grid = Grid::from_str(
+            "050703060007000800000816000000030000005000100730040086906000204840572093000409000",
+        )
+        .unwrap();
+        let rating = rate(&grid, None);
+        assert!(rating.solved);
+        assert_eq!(rating.score, 1.2);
+        assert_eq!(rating.hardest.as_deref(), Some("Naked Single"));
+        assert_eq!(rating.label(), "Easy");
+    }
+
+    #[test]
+    fn test_rate_solved_grid() {
+        let grid = Grid::from_str(
+            "436129875918375264275846193892654317164937528357218649683491752729563481541782936",
+        )
+        .unwrap();
+        let rating = rate(&grid, None);
+        assert_eq!((rating.score, rating.hardest, rating.steps), (0.0, None, 0));
+        assert!(rating.solved);
+    }
+}
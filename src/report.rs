@@ -0,0 +1,242 @@
+//! Solve reports as Markdown or plain text, for code review tools and wikis
+//! where HTML pages cannot be viewed.
+//!
+//! Every step is listed with its strategy, what it changed and, the first
+//! time a strategy is used, what it looks for. Pencilmark grids are shown
+//! at checkpoints and where the solve ends.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::solver::strategies::strategy_description;
+use crate::solver::{Solver, StepRecord};
+use crate::{Error, Grid, Result};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReportFormat {
+    Markdown,
+    Text,
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "text" | "txt" => Ok(Self::Text),
+            _ => Err(Error::new(&format!("Unknown report format: {}", s))),
+        }
+    }
+}
+
+/// Where grids are shown between the steps, on top of the end of the solve.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Checkpoints {
+    /// After every N steps
+    Every(usize),
+    /// After the last step of each run of the same strategy
+    StrategyChange,
+    /// Only where the solve ends
+    Stall,
+}
+
+impl FromStr for Checkpoints {
+    type Err = Error;
+    /// A number of steps, "strategy" or "stall".
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "strategy" => Ok(Self::StrategyChange),
+            "stall" => Ok(Self::Stall),
+            _ => match s.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Self::Every(n)),
+                _ => Err(Error::new(&format!("Unknown checkpoints: {}", s))),
+            },
+        }
+    }
+}
+
+impl Checkpoints {
+    /// Whether to show the grid after the step at index i.
+    fn after(&self, steps: &[StepRecord], i: usize) -> bool {
+        match *self {
+            Self::Every(n) => (i + 1) % n == 0,
+            Self::StrategyChange => steps
+                .get(i + 1)
+                .is_some_and(|next| next.strategy != steps[i].strategy),
+            Self::Stall => false,
+        }
+    }
+}
+
+pub struct ReportRenderer {
+    solver: Solver,
+    format: ReportFormat,
+    checkpoints: Checkpoints,
+}
+
+impl ReportRenderer {
+    pub fn new(solver: Solver) -> Self {
+        Self {
+            solver,
+            format: ReportFormat::Markdown,
+            checkpoints: Checkpoints::StrategyChange,
+        }
+    }
+
+    pub fn with_format(mut self, format: ReportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Solve the grid and render the report.
+    pub fn solve_and_render_report(&self, grid: &mut Grid) -> String {
+        let (mut history, steps) = self.solver.solve_history(grid);
+        let mut out = String::new();
+        self.heading(&mut out, "Solve report", 1);
+        writeln!(out, "Puzzle: {}\n", self.code(&history.grid().to_string())).unwrap();
+        self.grid(&mut out, "Start", history.grid());
+        let mut described = vec![];
+        for (i, step) in steps.iter().enumerate() {
+            history.redo();
+            let label = history.label(i + 1).unwrap_or_default();
+            let narration = format!("{}: {}", self.strong(label), step.delta);
+            writeln!(out, "{:>3}. {}", i + 1, narration).unwrap();
+            if !described.contains(&step.strategy) {
+                if let Some(description) = strategy_description(&step.strategy) {
+                    writeln!(out, "     {}", description).unwrap();
+                }
+                described.push(step.strategy.clone());
+            }
+            if i + 1 < steps.len() && self.checkpoints.after(&steps, i) {
+                out.push('\n');
+                self.grid(&mut out, &format!("After step {}", i + 1), history.grid());
+            }
+        }
+        if !steps.is_empty() {
+            out.push('\n');
+        }
+        let plural = if steps.len() == 1 { "" } else { "s" };
+        let end = if grid.is_solved() {
+            format!("Solved in {} step{}", steps.len(), plural)
+        } else {
+            format!("Stalled after {} step{}", steps.len(), plural)
+        };
+        self.grid(&mut out, &end, grid);
+        out
+    }
+
+    fn heading(&self, out: &mut String, title: &str, level: usize) {
+        match self.format {
+            ReportFormat::Markdown => writeln!(out, "{} {}\n", "#".repeat(level), title),
+            ReportFormat::Text if level == 1 => {
+                writeln!(out, "{}\n{}\n", title, "=".repeat(title.chars().count()))
+            }
+            ReportFormat::Text => writeln!(out, "{}:", title),
+        }
+        .unwrap();
+    }
+
+    fn grid(&self, out: &mut String, title: &str, grid: &Grid) {
+        self.heading(out, title, 2);
+        match self.format {
+            ReportFormat::Markdown => writeln!(out, "```text\n{}```\n", grid.to_pencilmarks()),
+            ReportFormat::Text => writeln!(out, "{}", grid.to_pencilmarks()),
+        }
+        .unwrap();
+    }
+
+    fn strong(&self, text: &str) -> String {
+        match self.format {
+            ReportFormat::Markdown => format!("**{}**", text),
+            ReportFormat::Text => text.to_string(),
+        }
+    }
+
+    fn code(&self, text: &str) -> String {
+        match self.format {
+            ReportFormat::Markdown => format!("`{}`", text),
+            ReportFormat::Text => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{NEARLY_SOLVED, PAIR_PUZZLE};
+    use crate::solver::strategies::all_strategies;
+
+    fn renderer() -> ReportRenderer {
+        ReportRenderer::new(Solver::new(all_strategies()))
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let mut grid = Grid::from_str(NEARLY_SOLVED).unwrap();
+        let report = renderer()
+            .with_checkpoints(Checkpoints::Every(1))
+            .solve_and_render_report(&mut grid);
+        assert!(grid.is_solved());
+        assert!(report.starts_with("# Solve report\n\nPuzzle: `036"));
+        assert!(report.contains("  1. **[Standard] Naked Single**: "));
+        assert_eq!(
+            report
+                .matches(strategy_description("Naked Single").unwrap())
+                .count(),
+            1
+        );
+        // Start, the steps but the last, then the end
+        let steps = report.matches("**[").count();
+        assert_eq!(report.matches("```text").count(), steps + 1);
+        assert!(report.contains(&format!("## Solved in {} step", steps)));
+    }
+
+    #[test]
+    fn test_report_is_stable() {
+        // Row 1 empty, so that steps change many cells
+        let puzzle =
+            "000000000918375264275846193892654317164937528357218649683491752729563481541782936";
+        let render = || renderer().solve_and_render_report(&mut Grid::from_str(puzzle).unwrap());
+        let report = render();
+        assert!(report.contains("R1C1-"));
+        for _ in 0..3 {
+            assert_eq!(render(), report);
+        }
+    }
+
+    #[test]
+    fn test_text_report_checkpoints() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let report = renderer()
+            .with_format(ReportFormat::Text)
+            .solve_and_render_report(&mut grid);
+        let labels: Vec<&str> = report
+            .lines()
+            .filter_map(|l| l.trim_start().split_once(". ["))
+            .filter(|(n, _)| n.parse::<usize>().is_ok())
+            .map(|(_, rest)| rest.split(':').next().unwrap())
+            .collect();
+        let changes = labels.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(changes > 1);
+        assert!(report.starts_with("Solve report\n============\n"));
+        assert!(!report.contains("**"));
+        assert_eq!(report.matches("After step").count(), changes);
+        // Start and end grids too
+        assert_eq!(report.matches("\n.-").count(), changes + 2);
+    }
+
+    #[test]
+    fn test_parse_checkpoints() {
+        assert_eq!(Checkpoints::from_str("5").unwrap(), Checkpoints::Every(5));
+        assert_eq!(
+            Checkpoints::from_str("strategy").unwrap(),
+            Checkpoints::StrategyChange
+        );
+        assert!(Checkpoints::from_str("0").is_err());
+    }
+}
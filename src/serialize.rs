@@ -0,0 +1,233 @@
+//! Serde support, enabled with the `serde` feature.
+//!
+//! The JSON schema is meant to be stable:
+//!
+//! - `CellValue`: an integer from 1 to 9
+//! - `Candidates`: an array of the candidate values in increasing order, `[1, 4, 7]`
+//! - `Cell`: `{"solved": 5}` or `{"unsolved": [1, 4, 7]}`
+//! - `Position`: `{"row": 3, "col": 8}`, rows and columns from 1 to 9
+//! - `Grid`: an array of the 81 cells in row order
+//! - `StrategyDelta`: `{"solve": [{"position": ..., "value": 5}, ...],
+//!   "eliminate": [{"position": ..., "candidates": [2, 3]}, ...],
+//!   "pattern": [...], "links": [...]}`. The pattern holds the cells behind
+//!   the deduction and the links those of a chain, as
+//!   `{"from": ..., "from_value": 3, "to": ..., "to_value": 3, "strong": true}`.
+//!   Links keep the order of the chain, the other lists are in row order, and
+//!   the pattern and links are left out when empty.
+//! - `Difficulty`: `"trivial"` or `"standard"`
+//! - `StepRecord`, the serializable form of a `SolutionStep`:
+//!   `{"strategy": "Naked Pair", "difficulty": "standard", "delta": ..., "time_us": 12}`
+//! - `SolveReport`: `{"input": "0030...", "output": "4132...", "status": "solved",
+//!   "time_us": 1520, "steps": [...]}`, with grids as 81-digit strings and
+//!   `"stalled"` status when the strategies could not finish the puzzle
+
+use std::convert::TryFrom;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::solver::strategies::{Link, StrategyDelta};
+use crate::{Candidates, Cell, CellValue, Grid, Position};
+
+impl Serialize for CellValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for CellValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let n = u8::deserialize(deserializer)?;
+        if !(1..=9).contains(&n) {
+            return Err(D::Error::custom(format!("invalid cell value {}", n)));
+        }
+        Ok(CellValue(n))
+    }
+}
+
+impl Serialize for Candidates {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_vec().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Candidates {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut candidates = Candidates::new([false; 9]);
+        for v in Vec::<CellValue>::deserialize(deserializer)? {
+            candidates.add(&v);
+        }
+        Ok(candidates)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PositionRepr {
+    row: u8,
+    col: u8,
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PositionRepr {
+            row: self.row,
+            col: self.col,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PositionRepr::deserialize(deserializer)?;
+        let bound = 1..=9;
+        if !bound.contains(&repr.row) || !bound.contains(&repr.col) {
+            return Err(D::Error::custom(format!(
+                "position out of bounds: row {}, col {}",
+                repr.row, repr.col
+            )));
+        }
+        Ok(Position::new(repr.col, repr.row))
+    }
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.cells[..].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cells = Vec::<Cell>::deserialize(deserializer)?;
+        let cells = <[Cell; 81]>::try_from(cells).map_err(|cells| {
+            D::Error::custom(format!("grid must have 81 cells, got {}", cells.len()))
+        })?;
+        Ok(Grid { cells })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Solve {
+    position: Position,
+    value: CellValue,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Eliminate {
+    position: Position,
+    candidates: Candidates,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StrategyDeltaRepr {
+    solve: Vec<Solve>,
+    eliminate: Vec<Eliminate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pattern: Vec<Position>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    links: Vec<Link>,
+}
+
+impl Serialize for StrategyDelta {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StrategyDeltaRepr {
+            solve: self
+                .solved()
+                .into_iter()
+                .map(|(position, value)| Solve { position, value })
+                .collect(),
+            eliminate: self
+                .eliminated()
+                .into_iter()
+                .map(|(position, candidates)| Eliminate {
+                    position,
+                    candidates,
+                })
+                .collect(),
+            pattern: self.pattern(),
+            links: self.links().to_vec(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StrategyDelta {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = StrategyDeltaRepr::deserialize(deserializer)?;
+        let mut delta = StrategyDelta::new();
+        for s in repr.solve {
+            delta.solve(s.position, s.value);
+        }
+        for e in repr.eliminate {
+            for v in e.candidates.to_vec() {
+                delta.eliminate(e.position, v);
+            }
+        }
+        for p in repr.pattern {
+            delta.add_pattern(p);
+        }
+        for link in repr.links {
+            delta.add_link(link);
+        }
+        Ok(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::{Solver, StepRecord};
+
+    #[test]
+    fn test_cell_schema() {
+        let mut candidates = Candidates::new([false; 9]);
+        candidates.add(&1.into());
+        candidates.add(&7.into());
+        assert_eq!(
+            serde_json::to_value(Cell::Unsolved(candidates)).unwrap(),
+            json!({"unsolved": [1, 7]})
+        );
+        assert_eq!(
+            serde_json::to_value(Cell::Solved(5.into())).unwrap(),
+            json!({"solved": 5})
+        );
+        assert_eq!(
+            serde_json::to_value(Position::new(8, 3)).unwrap(),
+            json!({"row": 3, "col": 8})
+        );
+    }
+
+    #[test]
+    fn test_grid_round_trip() {
+        let solver = Solver::new(all_strategies());
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        solver.solve_step(&grid).unwrap().delta.apply(&mut grid);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+    }
+
+    #[test]
+    fn test_step_round_trip() {
+        let solver = Solver::new(all_strategies());
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let record = StepRecord::from(&solver.solve_step(&grid).unwrap());
+        let value = serde_json::to_value(&record).unwrap();
+        assert_eq!(value["strategy"], json!("Naked Single"));
+        assert_eq!(value["difficulty"], json!("standard"));
+        assert_eq!(serde_json::from_value::<StepRecord>(value).unwrap(), record);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(serde_json::from_value::<CellValue>(json!(0)).is_err());
+        assert!(serde_json::from_value::<Position>(json!({"row": 10, "col": 1})).is_err());
+        assert!(serde_json::from_value::<Grid>(json!([{"solved": 1}])).is_err());
+    }
+}
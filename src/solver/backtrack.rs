@@ -0,0 +1,222 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::{Cell, Grid};
+
+/// Brute-force search used to count solutions and to build complete grids.
+/// Unlike the strategies, it is not meant to explain anything, only to be fast.
+/// Candidates of unsolved cells are respected, so a grid with pencilmarks
+/// only has the solutions allowed by its remaining candidates.
+struct Search {
+    values: [u8; 81],
+    allowed: [u16; 81],
+    rows: [u16; 9],
+    cols: [u16; 9],
+    boxes: [u16; 9],
+}
+
+const ALL: u16 = 0b1_1111_1111;
+
+impl Search {
+    fn new(grid: &Grid) -> Option<Self> {
+        let mut search = Search {
+            values: [0; 81],
+            allowed: [ALL; 81],
+            rows: [0; 9],
+            cols: [0; 9],
+            boxes: [0; 9],
+        };
+        for (i, cell) in grid.cells.iter().enumerate() {
+            match cell {
+                Cell::Solved(v) => {
+                    let n: u8 = (*v).into();
+                    let bit = 1 << (n - 1);
+                    let (r, c, b) = Self::units(i);
+                    if (search.rows[r] | search.cols[c] | search.boxes[b]) & bit != 0 {
+                        return None;
+                    }
+                    search.place(i, n);
+                }
+                Cell::Unsolved(candidates) => {
+                    let mut mask = 0;
+                    for v in candidates.to_vec() {
+                        let n: u8 = v.into();
+                        mask |= 1 << (n - 1);
+                    }
+                    search.allowed[i] = mask;
+                }
+            }
+        }
+        Some(search)
+    }
+
+    fn units(i: usize) -> (usize, usize, usize) {
+        let (r, c) = (i / 9, i % 9);
+        (r, c, (r / 3) * 3 + c / 3)
+    }
+
+    fn place(&mut self, i: usize, n: u8) {
+        let bit = 1 << (n - 1);
+        let (r, c, b) = Self::units(i);
+        self.values[i] = n;
+        self.rows[r] |= bit;
+        self.cols[c] |= bit;
+        self.boxes[b] |= bit;
+    }
+
+    fn unplace(&mut self, i: usize) {
+        let bit = !(1 << (self.values[i] - 1));
+        let (r, c, b) = Self::units(i);
+        self.values[i] = 0;
+        self.rows[r] &= bit;
+        self.cols[c] &= bit;
+        self.boxes[b] &= bit;
+    }
+
+    fn options(&self, i: usize) -> u16 {
+        let (r, c, b) = Self::units(i);
+        self.allowed[i] & !(self.rows[r] | self.cols[c] | self.boxes[b])
+    }
+
+    /// Empty cell with the fewest options, or None if the grid is full.
+    /// A cell with no options at all short-circuits the scan.
+    fn next_cell(&self) -> Option<(usize, u16)> {
+        let mut best: Option<(usize, u16)> = None;
+        for i in 0..81 {
+            if self.values[i] != 0 {
+                continue;
+            }
+            let opts = self.options(i);
+            let count = opts.count_ones();
+            if best.is_none() || count < best.unwrap().1.count_ones() {
+                best = Some((i, opts));
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn digits(opts: u16) -> Vec<u8> {
+        (1..=9).filter(|n| opts & (1 << (n - 1)) != 0).collect()
+    }
+
+    fn count(&mut self, limit: usize, found: &mut usize, first: &mut Option<[u8; 81]>) {
+        match self.next_cell() {
+            None => {
+                *found += 1;
+                if first.is_none() {
+                    *first = Some(self.values);
+                }
+            }
+            Some((i, opts)) => {
+                for n in Self::digits(opts) {
+                    self.place(i, n);
+                    self.count(limit, found, first);
+                    self.unplace(i);
+                    if *found >= limit {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn fill_random<R: Rng>(&mut self, rng: &mut R) -> bool {
+        match self.next_cell() {
+            None => true,
+            Some((i, opts)) => {
+                let mut digits = Self::digits(opts);
+                digits.shuffle(rng);
+                for n in digits {
+                    self.place(i, n);
+                    if self.fill_random(rng) {
+                        return true;
+                    }
+                    self.unplace(i);
+                }
+                false
+            }
+        }
+    }
+
+    fn to_grid(values: &[u8; 81]) -> Grid {
+        let mut grid = Grid::new();
+        for (i, n) in values.iter().enumerate() {
+            grid.cells[i] = Cell::from_u8(*n);
+        }
+        grid
+    }
+}
+
+/// Count the solutions of a grid, stopping once `limit` solutions are found.
+pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+    let mut found = 0;
+    if let Some(mut search) = Search::new(grid) {
+        search.count(limit, &mut found, &mut None);
+    }
+    found
+}
+
+pub fn has_unique_solution(grid: &Grid) -> bool {
+    count_solutions(grid, 2) == 1
+}
+
+/// The unique solution of a grid, or None if it has zero or several solutions.
+pub fn unique_solution(grid: &Grid) -> Option<Grid> {
+    let mut found = 0;
+    let mut first = None;
+    let mut search = Search::new(grid)?;
+    search.count(2, &mut found, &mut first);
+    match (found, first) {
+        (1, Some(values)) => Some(Search::to_grid(&values)),
+        _ => None,
+    }
+}
+
+/// A complete, valid grid chosen at random.
+pub fn random_solution<R: Rng>(rng: &mut R) -> Grid {
+    let mut search = Search::new(&Grid::new()).unwrap();
+    search.fill_random(rng);
+    Search::to_grid(&search.values)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::fixtures::{PUZZLE, SOLUTION};
+
+    #[test]
+    fn test_unique_solution() {
+        let grid = Grid::from_str(PUZZLE).unwrap();
+        assert_eq!(count_solutions(&grid, 10), 1);
+        assert_eq!(unique_solution(&grid).unwrap().to_string(), SOLUTION);
+    }
+
+    #[test]
+    fn test_count_stops_at_limit() {
+        assert_eq!(count_solutions(&Grid::new(), 5), 5);
+        assert_eq!(unique_solution(&Grid::new()), None);
+    }
+
+    #[test]
+    fn test_contradiction_has_no_solution() {
+        let mut s = PUZZLE.to_string();
+        s.replace_range(0..2, "99");
+        assert_eq!(count_solutions(&Grid::from_str(&s).unwrap(), 2), 0);
+    }
+
+    #[test]
+    fn test_random_solution_is_valid() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let grid = random_solution(&mut rng);
+        assert!(grid.is_solved());
+        assert_eq!(count_solutions(&grid, 2), 1);
+    }
+}
@@ -1,8 +1,11 @@
+use std::fmt;
 use std::time::{Duration, Instant};
 
 use super::Grid;
-use strategies::{Strategy, StrategyDelta, StrategyResult};
+use crate::history::History;
+use strategies::{Difficulty, Strategy, StrategyDelta, StrategyResult};
 
+pub mod backtrack;
 pub mod strategies;
 
 #[cfg(test)]
@@ -41,6 +44,38 @@ impl Solver {
         }
         steps
     }
+
+    /// Solve the grid, returning its history back at the initial grid,
+    /// with steps labelled by strategy, and the steps that make it.
+    pub fn solve_history(&self, grid: &mut Grid) -> (History, Vec<StepRecord>) {
+        let mut history = History::new(*grid);
+        let mut steps = vec![];
+        while let Some(step) = self.solve_step(history.grid()) {
+            steps.push(StepRecord::from(&step));
+            history.apply(&step.strategy.label(), &step.delta);
+        }
+        *grid = *history.grid();
+        history.jump(0);
+        (history, steps)
+    }
+
+    /// Solve a copy of the grid and summarize the result.
+    pub fn solve_report(&self, grid: &Grid) -> SolveReport {
+        let start = Instant::now();
+        let mut output = *grid;
+        let steps = self.solve(&mut output);
+        SolveReport {
+            input: grid.to_string(),
+            output: output.to_string(),
+            status: if output.is_solved() {
+                SolveStatus::Solved
+            } else {
+                SolveStatus::Stalled
+            },
+            time_us: start.elapsed().as_micros() as u64,
+            steps: steps.iter().map(StepRecord::from).collect(),
+        }
+    }
 }
 
 pub struct SolutionStep {
@@ -48,3 +83,61 @@ pub struct SolutionStep {
     pub delta: StrategyDelta,
     pub time: Duration,
 }
+
+/// Summary of a SolutionStep that can be stored or sent elsewhere,
+/// the strategy is kept by name only.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepRecord {
+    pub strategy: String,
+    pub difficulty: Difficulty,
+    pub delta: StrategyDelta,
+    pub time_us: u64,
+}
+
+impl From<&SolutionStep> for StepRecord {
+    fn from(step: &SolutionStep) -> Self {
+        StepRecord {
+            strategy: step.strategy.name(),
+            difficulty: step.strategy.difficulty(),
+            delta: step.delta.clone(),
+            time_us: step.time.as_micros() as u64,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum SolveStatus {
+    Solved,
+    /// No strategy can make progress
+    Stalled,
+}
+
+impl fmt::Display for SolveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Solved => "solved",
+                Self::Stalled => "stalled",
+            }
+        )
+    }
+}
+
+/// Result of solving a puzzle, with grids as 81-digit strings.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveReport {
+    pub input: String,
+    pub output: String,
+    pub status: SolveStatus,
+    pub time_us: u64,
+    pub steps: Vec<StepRecord>,
+}
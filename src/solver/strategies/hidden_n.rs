@@ -23,15 +23,22 @@ impl<const N: usize> UnitStrategy for HiddenN<N> {
             pmap.add_cell(p, cell);
         }
         for (hidden_vs, positions) in pmap.find_hidden(N) {
-            for p in positions {
-                if let Some(Cell::Unsolved(candidates)) = unit.get(&p) {
+            let mut found = false;
+            for p in &positions {
+                if let Some(Cell::Unsolved(candidates)) = unit.get(p) {
                     for candidate in candidates.to_vec() {
                         if !hidden_vs.contains(&candidate) {
-                            delta.eliminate(p, candidate);
+                            delta.eliminate(*p, candidate);
+                            found = true;
                         }
                     }
                 }
             }
+            if found {
+                for p in positions {
+                    delta.add_pattern(p);
+                }
+            }
         }
         delta
     }
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use dyn_clone::{clone_trait_object, DynClone};
 
+use crate::transform::Transform;
 use crate::{Candidates, Cell, CellValue, Grid, Position, Unit};
 
 mod hidden_n;
@@ -25,6 +26,37 @@ pub fn all_strategies() -> Vec<Box<dyn Strategy>> {
     ]
 }
 
+/// What a strategy looks for, for readers of a solve, or None for a
+/// strategy without a description.
+pub fn strategy_description(name: &str) -> Option<&'static str> {
+    match name {
+        "Naked Single" => Some(
+            "A solved cell rules out its value in the other cells of its row, \
+             column and box. A cell left with one candidate is solved.",
+        ),
+        "Hidden Single" => Some(
+            "A value that fits in only one cell of a row, column or box goes \
+             in that cell.",
+        ),
+        "Naked Pair" | "Naked Triple" | "Naked Quad" => Some(
+            "N cells of a row, column or box whose candidates are only N \
+             values hold those values between them, so the values are ruled \
+             out in the other cells of the unit.",
+        ),
+        "Hidden Pair" | "Hidden Triple" | "Hidden Quad" => Some(
+            "N values that fit in only N cells of a row, column or box go in \
+             those cells, so the other candidates of the cells are ruled out.",
+        ),
+        _ => None,
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Difficulty {
     Trivial = 0,
     Standard = 1,
@@ -49,9 +81,40 @@ pub enum StrategyResult {
     Failure,
 }
 
+/// A link between two candidates, drawn as an arrow. Chain strategies
+/// alternate strong links, where one of the two candidates is true, and weak
+/// links, where they are not both true.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Link {
+    pub from: Position,
+    pub from_value: CellValue,
+    pub to: Position,
+    pub to_value: CellValue,
+    pub strong: bool,
+}
+
+impl Link {
+    /// The same link for the transformed grid.
+    pub fn transform(&self, t: &Transform) -> Link {
+        Link {
+            from: t.map_position(self.from),
+            from_value: t.map_value(self.from_value),
+            to: t.map_position(self.to),
+            to_value: t.map_value(self.to_value),
+            strong: self.strong,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct StrategyDelta {
     solve: HashMap<Position, CellValue>,
     eliminate: HashMap<Position, Candidates>,
+    /// Cells that make the deduction, like the two cells of a naked pair
+    pattern: HashSet<Position>,
+    /// Links of a chain, in order
+    links: Vec<Link>,
 }
 
 impl StrategyDelta {
@@ -59,6 +122,8 @@ impl StrategyDelta {
         StrategyDelta {
             solve: HashMap::new(),
             eliminate: HashMap::new(),
+            pattern: HashSet::new(),
+            links: vec![],
         }
     }
 
@@ -73,6 +138,38 @@ impl StrategyDelta {
         self.solve.is_empty() && self.eliminate.is_empty()
     }
 
+    /// Cells solved by this delta, in row order.
+    pub fn solved(&self) -> Vec<(Position, CellValue)> {
+        let mut solved: Vec<(Position, CellValue)> =
+            self.solve.iter().map(|(p, v)| (*p, *v)).collect();
+        solved.sort_by_key(|(p, _)| (p.row(), p.col()));
+        solved
+    }
+
+    /// Candidates eliminated by this delta, in row order.
+    pub fn eliminated(&self) -> Vec<(Position, Candidates)> {
+        let mut eliminated: Vec<(Position, Candidates)> =
+            self.eliminate.iter().map(|(p, c)| (*p, *c)).collect();
+        eliminated.sort_by_key(|(p, _)| (p.row(), p.col()));
+        eliminated
+    }
+
+    /// Cells of the pattern behind the deduction, in row order.
+    pub fn pattern(&self) -> Vec<Position> {
+        let mut pattern: Vec<Position> = self.pattern.iter().copied().collect();
+        pattern.sort_by_key(|p| (p.row(), p.col()));
+        pattern
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// What the delta does to a cell.
+    pub fn change(&self, pos: Position) -> CellChange {
+        CellChange::new(self, pos)
+    }
+
     pub fn solve(&mut self, pos: Position, v: CellValue) {
         self.solve.insert(pos, v);
     }
@@ -84,10 +181,23 @@ impl StrategyDelta {
             .add(&v);
     }
 
+    /// Add a cell to the pattern. It does not change the grid, so a delta
+    /// with only a pattern is still empty.
+    pub fn add_pattern(&mut self, pos: Position) {
+        self.pattern.insert(pos);
+    }
+
+    /// Add a link to the chain. Like the pattern it does not change the grid.
+    pub fn add_link(&mut self, link: Link) {
+        self.links.push(link);
+    }
+
     // For non-overlapping positions only!
     pub fn extend(&mut self, other: StrategyDelta) {
         self.solve.extend(other.solve);
         self.eliminate.extend(other.eliminate);
+        self.pattern.extend(other.pattern);
+        self.links.extend(other.links);
     }
 
     pub fn apply(&self, grid: &mut Grid) {
@@ -104,16 +214,66 @@ impl StrategyDelta {
             }
         }
     }
+
+    /// The same delta for the transformed grid.
+    pub fn transform(&self, t: &Transform) -> StrategyDelta {
+        StrategyDelta {
+            solve: self
+                .solve
+                .iter()
+                .map(|(p, v)| (t.map_position(*p), t.map_value(*v)))
+                .collect(),
+            eliminate: self
+                .eliminate
+                .iter()
+                .map(|(p, c)| (t.map_position(*p), t.map_candidates(c)))
+                .collect(),
+            pattern: self.pattern.iter().map(|p| t.map_position(*p)).collect(),
+            links: self.links.iter().map(|l| l.transform(t)).collect(),
+        }
+    }
+}
+
+/// What a step did to a cell.
+#[derive(Copy, Clone, Debug)]
+pub struct CellChange {
+    /// Candidates removed from the cell
+    pub eliminated: Candidates,
+    /// The delta solves the cell, see is_placed for cells solved by eliminations
+    pub placed: bool,
+    /// The cell is part of the pattern behind the deduction
+    pub pattern: bool,
+}
+
+impl CellChange {
+    pub fn new(delta: &StrategyDelta, pos: Position) -> Self {
+        Self {
+            eliminated: delta
+                .eliminate
+                .get(&pos)
+                .copied()
+                .unwrap_or_else(|| Candidates::new([false; 9])),
+            placed: delta.solve.contains_key(&pos),
+            pattern: delta.pattern.contains(&pos),
+        }
+    }
+
+    /// True if the cell, as it is after the step, was solved by it. Eliminating
+    /// all candidates but one also solves a cell.
+    pub fn is_placed(&self, cell: &Cell) -> bool {
+        self.placed || (matches!(cell, Cell::Solved(_)) && self.eliminated.count() > 0)
+    }
 }
 
 impl fmt::Display for StrategyDelta {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        // In row order, so that the same step always reads the same
         let mut parts = vec![];
-        for (p, v) in &self.solve {
-            let n: u8 = (*v).into();
+        for (p, v) in self.solved() {
+            let n: u8 = v.into();
             parts.push(format!("{}={}", p, n));
         }
-        for (p, cdx) in &self.eliminate {
+        for (p, cdx) in self.eliminated() {
             parts.push(format!("{}-{}", p, cdx));
         }
         write!(f, "{}", parts.join(", "))
@@ -125,6 +285,17 @@ pub trait Strategy: DynClone {
     fn name(&self) -> String;
     fn difficulty(&self) -> Difficulty;
     fn solve(&self, grid: &Grid) -> StrategyDelta;
+
+    /// Name with the difficulty, as shown in captions of rendered steps.
+    fn label(&self) -> String {
+        format!("[{}] {}", self.difficulty(), self.name())
+    }
+
+    /// The first unit where the strategy applies, with its delta in that unit
+    /// only. None for strategies that do not work unit by unit.
+    fn solve_first_unit(&self, _grid: &Grid) -> Option<(Vec<Position>, StrategyDelta)> {
+        None
+    }
 }
 
 clone_trait_object!(Strategy);
@@ -171,4 +342,15 @@ impl<T: UnitStrategy + Clone> Strategy for UnitStrategyWrapper<T> {
         }
         delta
     }
+
+    fn solve_first_unit(&self, grid: &Grid) -> Option<(Vec<Position>, StrategyDelta)> {
+        Position::unit_vecs().into_iter().find_map(|vec| {
+            let delta = self.0.solve_unit(&grid.get_cells(vec.clone()));
+            if delta.is_empty() {
+                None
+            } else {
+                Some((vec, delta))
+            }
+        })
+    }
 }
@@ -23,18 +23,25 @@ impl<const N: usize> UnitStrategy for NakedN<N> {
             cmap.add_cell(p, cell);
         }
         for (naked_ps, naked_vs) in cmap.find_naked(N) {
+            let mut found = false;
             for (p, cell) in unit {
                 if naked_ps.contains(p) {
                     continue;
                 }
                 if let Some(candidates) = cell.candidates() {
-                    for candidate in candidates.to_vec() {
+                    for candidate in candidates {
                         if naked_vs.contains(&candidate) {
                             delta.eliminate(*p, candidate);
+                            found = true;
                         }
                     }
                 }
             }
+            if found {
+                for p in naked_ps {
+                    delta.add_pattern(p);
+                }
+            }
         }
         delta
     }
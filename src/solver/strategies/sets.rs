@@ -23,7 +23,7 @@ where
     }
 
     pub fn insert(&mut self, k: K, v: V) {
-        self.0.entry(k).or_insert_with(HashSet::new).insert(v);
+        self.0.entry(k).or_default().insert(v);
     }
 
     fn rec_find_critical_sets(
@@ -73,6 +73,40 @@ where
     }
 }
 
+pub struct Order(pub usize);
+
+impl From<Order> for usize {
+    fn from(order: Order) -> Self {
+        order.0
+    }
+}
+
+impl From<usize> for Order {
+    fn from(n: usize) -> Self {
+        Order(n)
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}",
+            if self.0 == 1 {
+                "Single".to_string()
+            } else if self.0 == 2 {
+                "Pair".to_string()
+            } else if self.0 == 3 {
+                "Triple".to_string()
+            } else if self.0 == 4 {
+                "Quad".to_string()
+            } else {
+                format!("Order<{}>", self.0)
+            }
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,37 +156,3 @@ mod tests {
         assert!(csets.contains(&(hashset![1, 2, 3], hashset![1, 2, 3])));
     }
 }
-
-pub struct Order(pub usize);
-
-impl From<Order> for usize {
-    fn from(order: Order) -> Self {
-        order.0
-    }
-}
-
-impl From<usize> for Order {
-    fn from(n: usize) -> Self {
-        Order(n)
-    }
-}
-
-impl fmt::Display for Order {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::result::Result<(), fmt::Error> {
-        write!(
-            f,
-            "{}",
-            if self.0 == 1 {
-                "Single".to_string()
-            } else if self.0 == 2 {
-                "Pair".to_string()
-            } else if self.0 == 3 {
-                "Triple".to_string()
-            } else if self.0 == 4 {
-                "Quad".to_string()
-            } else {
-                format!("Order<{}>", self.0)
-            }
-        )
-    }
-}
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use test_case::test_case;
 
-use crate::{solver::strategies::all_strategies, solver::Solver, Grid};
+use crate::{solver::strategies::all_strategies, solver::Solver, Candidates, Cell, Grid};
 
 #[test_case(
     "000120000900005060075040093092050007164030528300010640680090750020500001000082000",
@@ -45,3 +45,42 @@ fn solver_test(sudoku: &str, solution: &str) {
     solver.solve(&mut sudoku);
     assert_eq!(solution.to_string(), sudoku.to_string());
 }
+
+/// States captured with Grid::to_base64 after each step of solving the
+/// naked-pair puzzle, replaying them must give the same steps.
+#[test]
+fn replay_captured_states() {
+    let solver = Solver::new(all_strategies());
+    let states: Vec<Grid> = include_str!("fixtures/naked_pair_states.txt")
+        .lines()
+        .map(|s| Grid::from_base64(s).unwrap())
+        .collect();
+    for pair in states.windows(2) {
+        let mut grid = pair[0];
+        solver.solve_step(&grid).unwrap().delta.apply(&mut grid);
+        assert_eq!(grid, pair[1]);
+    }
+    // An unsolved cell with a single candidate left must not come back
+    // solved: narrow the first unsolved cell to its value in the solution
+    let last = *states.last().unwrap();
+    let mut single = states[0];
+    let i = single
+        .cells
+        .iter()
+        .position(|c| matches!(c, Cell::Unsolved(_)))
+        .unwrap();
+    let mut candidates = Candidates::new([false; 9]);
+    if let Cell::Solved(v) = last.cells[i] {
+        candidates.add(&v);
+    }
+    single.cells[i] = Cell::Unsolved(candidates);
+    for grid in states.iter().chain([&single]) {
+        assert_eq!(Grid::from_base64(&grid.to_base64()).unwrap(), *grid);
+        assert_eq!(
+            Grid::from_candidate_string(&grid.to_candidate_string()).unwrap(),
+            *grid
+        );
+    }
+    assert!(last.is_solved());
+    assert!(solver.solve_step(&last).is_none());
+}
@@ -203,11 +203,7 @@ pub struct Report<T> {
 
 impl<T: Copy> Report<T> {
     pub fn update(&mut self, field: &str, value: T) {
-        for stat in self
-            .fields
-            .entry(field.to_string())
-            .or_insert_with(Vec::new)
-        {
+        for stat in self.fields.entry(field.to_string()).or_default() {
             stat.update(value);
         }
     }
@@ -238,10 +234,7 @@ impl<T: Copy> ReportBuilder<T> {
         }
     }
     pub fn with(mut self, field: &str, stat: Formatted<T>) -> Self {
-        self.fields
-            .entry(field.to_string())
-            .or_insert_with(Vec::new)
-            .push(stat);
+        self.fields.entry(field.to_string()).or_default().push(stat);
         let f = field.to_string();
         if !self.order.contains(&f) {
             self.order.push(f);
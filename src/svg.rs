@@ -0,0 +1,392 @@
+//! SVG rendering of grids and solver steps, for print and slides.
+//!
+//! Steps are highlighted like in the HTML pages: pattern cells get a
+//! background, eliminated candidates are struck out in red, placed values
+//! are green and chain links are drawn as arrows, dashed for weak links.
+//! A whole solve can also be rendered as an animation, played with SMIL so
+//! it needs no scripts.
+
+use std::fmt::Write;
+
+use crate::solver::strategies::{Link, StrategyDelta};
+use crate::solver::SolutionStep;
+use crate::{Cell, CellValue, Grid, Position};
+
+const CELL: u32 = 60;
+const MARGIN: u32 = 10;
+const SIZE: u32 = 9 * CELL + 2 * MARGIN;
+/// Height of the caption under the grid in animations
+const CAPTION: u32 = 50;
+
+const GIVEN: &str = "#111111";
+const SOLVED: &str = "#3F6FCF";
+const MARK: &str = "#626262";
+const PLACED: &str = "#2E9E3E";
+const ELIMINATED: &str = "#CF3F3F";
+const PATTERN: &str = "#FFF1B8";
+const LINK: &str = "#7F3FBF";
+
+/// Arrow head for the links.
+fn arrow_marker() -> String {
+    format!(
+        concat!(
+            r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" "#,
+            r#"markerWidth="6" markerHeight="6" orient="auto">"#,
+            r#"<path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#,
+            "\n"
+        ),
+        LINK
+    )
+}
+
+/// Opening svg element and white background, the grid being at the top.
+fn open_svg(height: u32) -> String {
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" "#,
+            r#"viewBox="0 0 {0} {1}" font-family="sans-serif">"#,
+            "\n",
+            r#"<rect width="{0}" height="{1}" fill="white"/>"#,
+            "\n"
+        ),
+        SIZE, height
+    )
+}
+
+/// Top left corner of a cell.
+fn cell_origin(pos: Position) -> (u32, u32) {
+    (
+        MARGIN + (pos.col() as u32 - 1) * CELL,
+        MARGIN + (pos.row() as u32 - 1) * CELL,
+    )
+}
+
+/// Centre of a candidate, candidates being laid out like a phone keypad.
+fn candidate_center(pos: Position, v: CellValue) -> (u32, u32) {
+    let (x, y) = cell_origin(pos);
+    let i = u8::from(v) as u32 - 1;
+    let third = CELL / 3;
+    (x + i % 3 * third + third / 2, y + i / 3 * third + third / 2)
+}
+
+pub struct GridRenderer<'a> {
+    grid: &'a Grid,
+    puzzle: Option<&'a Grid>,
+    delta: Option<&'a StrategyDelta>,
+}
+
+impl<'a> GridRenderer<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Self {
+            grid,
+            puzzle: None,
+            delta: None,
+        }
+    }
+
+    /// Draw the values of the puzzle as givens and the others as solved.
+    /// Without it every value is drawn as a given.
+    pub fn with_puzzle(mut self, puzzle: &'a Grid) -> Self {
+        self.puzzle = Some(puzzle);
+        self
+    }
+
+    /// Highlight the changes of the step that led to the grid.
+    pub fn with_delta(mut self, delta: &'a StrategyDelta) -> Self {
+        self.delta = Some(delta);
+        self
+    }
+
+    fn is_given(&self, pos: Position) -> bool {
+        self.puzzle
+            .is_none_or(|puzzle| matches!(puzzle.get_cell(pos), Cell::Solved(_)))
+    }
+
+    pub fn render(&self) -> String {
+        let mut svg = open_svg(SIZE);
+        if self.delta.is_some_and(|d| !d.links().is_empty()) {
+            svg.push_str(&arrow_marker());
+        }
+        self.render_grid(&mut svg);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The cells, lines and links, without the enclosing svg element.
+    fn render_grid(&self, svg: &mut String) {
+        for pos in Position::row_vecs().into_iter().flatten() {
+            self.render_cell(svg, pos);
+        }
+        self.render_lines(svg);
+        for link in self.delta.map(|d| d.links()).unwrap_or_default() {
+            render_link(svg, link);
+        }
+    }
+
+    fn render_cell(&self, svg: &mut String, pos: Position) {
+        let (x, y) = cell_origin(pos);
+        let cell = self.grid.get_cell(pos);
+        let change = self.delta.map(|d| d.change(pos));
+        if change.is_some_and(|c| c.pattern) {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#,
+                x, y, CELL, PATTERN
+            )
+            .unwrap();
+        }
+        match cell {
+            Cell::Solved(v) => {
+                let color = if change.is_some_and(|c| c.is_placed(&cell)) {
+                    PLACED
+                } else if self.is_given(pos) {
+                    GIVEN
+                } else {
+                    SOLVED
+                };
+                writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}" font-size="40" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x + CELL / 2,
+                    y + CELL / 2,
+                    color,
+                    u8::from(v)
+                )
+                .unwrap();
+            }
+            Cell::Unsolved(candidates) => {
+                for n in 1..=9 {
+                    let v = CellValue::new(n);
+                    let (cx, cy) = candidate_center(pos, v);
+                    let eliminated = change.is_some_and(|c| c.eliminated.can_be(&v));
+                    let color = if candidates.can_be(&v) {
+                        MARK
+                    } else if eliminated {
+                        ELIMINATED
+                    } else {
+                        continue;
+                    };
+                    writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-size="14" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                        cx, cy, color, n
+                    )
+                    .unwrap();
+                    if eliminated {
+                        writeln!(
+                            svg,
+                            r#"<line x1="{}" y1="{2}" x2="{}" y2="{2}" stroke="{3}" stroke-width="1.5"/>"#,
+                            cx - 6,
+                            cx + 6,
+                            cy,
+                            ELIMINATED
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_lines(&self, svg: &mut String) {
+        let end = MARGIN + 9 * CELL;
+        for i in 0..=9 {
+            let at = MARGIN + i * CELL;
+            let (color, width) = if i % 3 == 0 { (GIVEN, 3) } else { (MARK, 1) };
+            writeln!(
+                svg,
+                r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="{3}" stroke-width="{4}" stroke-linecap="square"/>"#,
+                at, MARGIN, end, color, width
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}" stroke="{3}" stroke-width="{4}" stroke-linecap="square"/>"#,
+                at, MARGIN, end, color, width
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// A solve played back step by step, each step showing the grid after it
+/// with its changes highlighted and a caption naming the strategy. The
+/// animation loops.
+pub struct AnimationRenderer<'a> {
+    puzzle: &'a Grid,
+    steps: &'a [SolutionStep],
+    frames_per_step: u32,
+    frame_rate: u32,
+}
+
+impl<'a> AnimationRenderer<'a> {
+    /// Steps as returned by Solver::solve for the puzzle.
+    pub fn new(puzzle: &'a Grid, steps: &'a [SolutionStep]) -> Self {
+        Self {
+            puzzle,
+            steps,
+            frames_per_step: 24,
+            frame_rate: 12,
+        }
+    }
+
+    /// Number of frames each step stays on screen.
+    pub fn with_frames_per_step(mut self, frames: u32) -> Self {
+        self.frames_per_step = frames.max(1);
+        self
+    }
+
+    /// Frames per second, so a step lasts frames_per_step / frame_rate seconds.
+    pub fn with_frame_rate(mut self, fps: u32) -> Self {
+        self.frame_rate = fps.max(1);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut svg = open_svg(SIZE + CAPTION);
+        if self.steps.iter().any(|s| !s.delta.links().is_empty()) {
+            svg.push_str(&arrow_marker());
+        }
+        let mut grid = *self.puzzle;
+        let renderer = GridRenderer::new(&grid).with_puzzle(self.puzzle);
+        self.render_frame(&mut svg, 0, &renderer, "Start");
+        for (i, step) in self.steps.iter().enumerate() {
+            step.delta.apply(&mut grid);
+            let renderer = GridRenderer::new(&grid)
+                .with_puzzle(self.puzzle)
+                .with_delta(&step.delta);
+            self.render_frame(&mut svg, i + 1, &renderer, &step.strategy.label());
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// A group shown only during its step of the loop.
+    fn render_frame(&self, svg: &mut String, i: usize, renderer: &GridRenderer, caption: &str) {
+        let count = self.steps.len() + 1;
+        let at = |i: usize| format!("{:.6}", i as f64 / count as f64);
+        let (values, key_times) = if count == 1 {
+            ("visible", "0".to_string())
+        } else if i == 0 {
+            ("visible;hidden", format!("0;{}", at(1)))
+        } else if i == count - 1 {
+            ("hidden;visible", format!("0;{}", at(i)))
+        } else {
+            (
+                "hidden;visible;hidden",
+                format!("0;{};{}", at(i), at(i + 1)),
+            )
+        };
+        let duration = (self.frames_per_step * count as u32) as f64 / self.frame_rate as f64;
+        writeln!(
+            svg,
+            r#"<g visibility="{}"><animate attributeName="visibility" values="{}" keyTimes="{}" dur="{}s" calcMode="discrete" repeatCount="indefinite"/>"#,
+            if i == 0 { "visible" } else { "hidden" },
+            values,
+            key_times,
+            duration
+        )
+        .unwrap();
+        renderer.render_grid(svg);
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="24" fill="{}" text-anchor="middle" dominant-baseline="central">{}. {}</text>"#,
+            SIZE / 2,
+            SIZE + CAPTION / 2,
+            GIVEN,
+            i,
+            caption
+        )
+        .unwrap();
+        svg.push_str("</g>\n");
+    }
+}
+
+/// Arrow between the two candidates, stopping short of the digits.
+fn render_link(svg: &mut String, link: &Link) {
+    let (x1, y1) = candidate_center(link.from, link.from_value);
+    let (x2, y2) = candidate_center(link.to, link.to_value);
+    let (x1, y1, x2, y2) = (x1 as f64, y1 as f64, x2 as f64, y2 as f64);
+    let length = (x2 - x1).hypot(y2 - y1);
+    if length == 0.0 {
+        return;
+    }
+    let gap = 8.0 / length;
+    let dash = if link.strong {
+        ""
+    } else {
+        r#" stroke-dasharray="4 3""#
+    };
+    writeln!(
+        svg,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"{} marker-end="url(#arrow)"/>"#,
+        x1 + (x2 - x1) * gap,
+        y1 + (y2 - y1) * gap,
+        x2 - (x2 - x1) * gap,
+        y2 - (y2 - y1) * gap,
+        LINK,
+        dash
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::Solver;
+
+    #[test]
+    fn test_render_grid() {
+        let grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let svg = GridRenderer::new(&grid).render();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        let givens = PAIR_PUZZLE.chars().filter(|c| *c != '0').count();
+        assert_eq!(svg.matches(r#"font-size="40""#).count(), givens);
+        assert!(!svg.contains("marker"));
+    }
+
+    #[test]
+    fn test_render_step() {
+        let puzzle = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let mut grid = puzzle;
+        let mut delta = Solver::new(all_strategies())
+            .solve_step(&grid)
+            .unwrap()
+            .delta;
+        delta.apply(&mut grid);
+        delta.add_link(Link {
+            from: Position::new(2, 1),
+            from_value: 1.into(),
+            to: Position::new(2, 4),
+            to_value: 1.into(),
+            strong: false,
+        });
+        let svg = GridRenderer::new(&grid)
+            .with_puzzle(&puzzle)
+            .with_delta(&delta)
+            .render();
+        assert!(svg.contains(PATTERN));
+        assert!(svg.contains(ELIMINATED));
+        assert!(svg.contains(r#"marker-end="url(#arrow)""#));
+        assert!(svg.contains("stroke-dasharray"));
+    }
+
+    #[test]
+    fn test_render_animation() {
+        let puzzle = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let steps = Solver::new(all_strategies()).solve(&mut puzzle.clone());
+        let svg = AnimationRenderer::new(&puzzle, &steps)
+            .with_frames_per_step(6)
+            .with_frame_rate(3)
+            .render();
+        assert_eq!(svg.matches("<animate ").count(), steps.len() + 1);
+        assert_eq!(svg.matches(r#"<g visibility="visible">"#).count(), 1);
+        assert!(svg.contains(&format!(r#"dur="{}s""#, 2 * (steps.len() + 1))));
+        assert!(svg.contains("1. [Standard] Naked Single"));
+    }
+}
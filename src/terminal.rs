@@ -0,0 +1,167 @@
+//! Grids drawn with box-drawing characters for terminals, with the
+//! candidates of each unsolved cell as a 3x3 mini-grid.
+//!
+//! With colours on, the changes of a step are highlighted with ANSI codes
+//! like in the HTML pages: pattern cells on a yellow background,
+//! eliminated candidates in red and placed values in green.
+
+use crate::solver::strategies::StrategyDelta;
+use crate::{Cell, CellValue, Grid, Position};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[1;32m";
+const PATTERN: &str = "\x1b[43;30m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// A horizontal border, from the left, cell, light and heavy crossing and
+/// right characters.
+fn border(left: char, fill: char, light: char, heavy: char, right: char) -> String {
+    let mut line = String::new();
+    line.push(left);
+    for col in 1..=9 {
+        line.extend([fill; 3]);
+        line.push(match col {
+            9 => right,
+            3 | 6 => heavy,
+            _ => light,
+        });
+    }
+    line.push('\n');
+    line
+}
+
+pub struct GridRenderer<'a> {
+    grid: &'a Grid,
+    delta: Option<&'a StrategyDelta>,
+    color: bool,
+}
+
+impl<'a> GridRenderer<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Self {
+            grid,
+            delta: None,
+            color: false,
+        }
+    }
+
+    /// Highlight the changes of the step that led to the grid, which
+    /// needs colours.
+    pub fn with_delta(mut self, delta: &'a StrategyDelta) -> Self {
+        self.delta = Some(delta);
+        self
+    }
+
+    /// Use ANSI colours.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = border('╔', '═', '╤', '╦', '╗');
+        for row in 1..=9 {
+            for line in 0..3 {
+                out.push('║');
+                for col in 1..=9 {
+                    out.push_str(&self.cell_line(Position::new(col, row), line));
+                    out.push(if col % 3 == 0 { '║' } else { '│' });
+                }
+                out.push('\n');
+            }
+            out.push_str(&match row {
+                9 => border('╚', '═', '╧', '╩', '╝'),
+                3 | 6 => border('╠', '═', '╪', '╬', '╣'),
+                _ => border('╟', '─', '┼', '╫', '╢'),
+            });
+        }
+        out
+    }
+
+    /// One of the three lines of a cell: a third of the candidates, or
+    /// the value on the middle line.
+    fn cell_line(&self, pos: Position, line: u8) -> String {
+        let cell = self.grid.get_cell(pos);
+        let change = self.delta.filter(|_| self.color).map(|d| d.change(pos));
+        let pattern = change.is_some_and(|c| c.pattern);
+        let text = match cell {
+            Cell::Solved(v) if line == 1 => {
+                let color = match change {
+                    Some(c) if c.is_placed(&cell) => GREEN,
+                    _ if self.color => BOLD,
+                    _ => "",
+                };
+                format!(" {} ", paint(u8::from(v), color, pattern))
+            }
+            Cell::Solved(_) => "   ".to_string(),
+            Cell::Unsolved(candidates) => (1..=3)
+                .map(|i| {
+                    let n = line * 3 + i;
+                    let v = CellValue::new(n);
+                    if candidates.can_be(&v) {
+                        n.to_string()
+                    } else if change.is_some_and(|c| c.eliminated.can_be(&v)) {
+                        paint(n, RED, pattern)
+                    } else {
+                        " ".to_string()
+                    }
+                })
+                .collect(),
+        };
+        if pattern {
+            format!("{}{}{}", PATTERN, text, RESET)
+        } else {
+            text
+        }
+    }
+}
+
+/// Text in a colour, keeping the pattern background after it.
+fn paint(text: u8, color: &str, pattern: bool) -> String {
+    if color.is_empty() {
+        return text.to_string();
+    }
+    let after = if pattern { PATTERN } else { "" };
+    format!("{}{}{}{}", color, text, RESET, after)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::fixtures::PAIR_PUZZLE;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::Solver;
+
+    #[test]
+    fn test_render_plain() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let step = Solver::new(all_strategies()).solve_step(&grid).unwrap();
+        step.delta.apply(&mut grid);
+        let out = GridRenderer::new(&grid).with_delta(&step.delta).render();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 37);
+        assert!(lines.iter().all(|l| l.chars().count() == 37));
+        assert!(!out.contains('\x1b'));
+        assert_eq!(lines[0], "╔═══╤═══╤═══╦═══╤═══╤═══╦═══╤═══╤═══╗");
+        // R1C1 is a 4, the step removes 3, 4 and 8 from the rest of row 1
+        assert!(lines[1].starts_with("║   │12 │"));
+        assert!(lines[2].starts_with("║ 4 │ 56│"));
+        assert!(lines[3].starts_with("║   │7 9│"));
+    }
+
+    #[test]
+    fn test_render_colors() {
+        let mut grid = Grid::from_str(PAIR_PUZZLE).unwrap();
+        let step = Solver::new(all_strategies()).solve_step(&grid).unwrap();
+        step.delta.apply(&mut grid);
+        let out = GridRenderer::new(&grid)
+            .with_delta(&step.delta)
+            .with_color(true)
+            .render();
+        assert!(out.contains(&format!("{}4{}", RED, RESET)));
+        assert!(out.contains(PATTERN));
+    }
+}
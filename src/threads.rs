@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
+use std::sync::{mpsc, Mutex};
+use std::thread;
 
 #[derive(Debug)]
 pub enum ThreadMode {
@@ -51,3 +54,71 @@ impl From<usize> for ThreadMode {
         Self::new(n)
     }
 }
+
+/// Apply f to each item, on several threads if the mode allows it, and pass
+/// the results to sink in the order of the items as soon as they are ready.
+/// Items are pulled lazily, so the iterator can be a stream like stdin.
+pub fn map_ordered<T, R, I, F, S>(mode: &ThreadMode, items: I, f: F, mut sink: S)
+where
+    T: Send,
+    R: Send,
+    I: Iterator<Item = T> + Send,
+    F: Fn(T) -> R + Sync,
+    S: FnMut(R),
+{
+    let threads = match mode {
+        ThreadMode::SingleThreaded => {
+            for item in items {
+                sink(f(item));
+            }
+            return;
+        }
+        ThreadMode::MultiThreaded(n) => *n,
+    };
+    let (job_tx, job_rx) = mpsc::sync_channel::<(usize, T)>(threads * 4);
+    let (result_tx, result_rx) = mpsc::channel::<(usize, R)>();
+    let job_rx = Mutex::new(job_rx);
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for job in items.enumerate() {
+                if job_tx.send(job).is_err() {
+                    break;
+                }
+            }
+        });
+        for _ in 0..threads {
+            let (job_rx, result_tx, f) = (&job_rx, result_tx.clone(), &f);
+            scope.spawn(move || loop {
+                let job = job_rx.lock().unwrap().recv();
+                match job {
+                    Ok((i, item)) => result_tx.send((i, f(item))).unwrap(),
+                    Err(_) => break,
+                }
+            });
+        }
+        drop(result_tx);
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in result_rx {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                sink(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_ordered_keeps_order() {
+        for mode in [ThreadMode::SingleThreaded, ThreadMode::MultiThreaded(4)].iter() {
+            let mut out = vec![];
+            map_ordered(mode, 0..100u64, |n| n * n, |r| out.push(r));
+            assert_eq!(out, (0..100u64).map(|n| n * n).collect::<Vec<u64>>());
+        }
+    }
+}
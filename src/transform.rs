@@ -0,0 +1,341 @@
+use crate::{Candidates, Cell, CellValue, Error, Grid, Position, Result};
+
+const IDENTITY: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+const REVERSED: [u8; 9] = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+
+/// A transformation that maps valid grids to valid grids.
+///
+/// Every combination of the sudoku symmetries can be written as an optional
+/// transpose, followed by a permutation of the rows and of the columns that
+/// keeps bands and stacks together, plus a relabelling of the digits.
+/// Permutations are stored 0-based, rows[i] is where row i ends up.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Transform {
+    transpose: bool,
+    rows: [u8; 9],
+    cols: [u8; 9],
+    digits: [u8; 9],
+}
+
+fn compose(first: &[u8; 9], second: &[u8; 9]) -> [u8; 9] {
+    let mut out = [0; 9];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = second[first[i] as usize];
+    }
+    out
+}
+
+fn invert(perm: &[u8; 9]) -> [u8; 9] {
+    let mut out = [0; 9];
+    for (i, p) in perm.iter().enumerate() {
+        out[*p as usize] = i as u8;
+    }
+    out
+}
+
+fn swap(a: u8, b: u8) -> [u8; 9] {
+    let mut perm = IDENTITY;
+    perm.swap(a as usize, b as usize);
+    perm
+}
+
+fn swap_groups(a: u8, b: u8) -> [u8; 9] {
+    let mut perm = IDENTITY;
+    for i in 0..3 {
+        perm.swap((a * 3 + i) as usize, (b * 3 + i) as usize);
+    }
+    perm
+}
+
+/// Check that two 1-based row or column numbers are in the same band or stack.
+fn check_same_group(a: u8, b: u8) -> Result<()> {
+    check_range(a)?;
+    check_range(b)?;
+    if (a - 1) / 3 != (b - 1) / 3 {
+        return Err(Error::new(&format!(
+            "{} and {} are not in the same band or stack",
+            a, b
+        )));
+    }
+    Ok(())
+}
+
+fn check_band(n: u8) -> Result<()> {
+    if !(1..=3).contains(&n) {
+        return Err(Error::new(&format!("Band or stack {} is out of range", n)));
+    }
+    Ok(())
+}
+
+fn check_range(n: u8) -> Result<()> {
+    if !(1..=9).contains(&n) {
+        return Err(Error::new(&format!("{} is out of range", n)));
+    }
+    Ok(())
+}
+
+impl Transform {
+    fn new(transpose: bool, rows: [u8; 9], cols: [u8; 9]) -> Self {
+        Self {
+            transpose,
+            rows,
+            cols,
+            digits: IDENTITY,
+        }
+    }
+
+    /// Build from 0-based permutations, rows and cols must keep bands
+    /// and stacks together.
+    pub(crate) fn from_permutations(
+        transpose: bool,
+        rows: [u8; 9],
+        cols: [u8; 9],
+        digits: [u8; 9],
+    ) -> Self {
+        Self {
+            transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(false, IDENTITY, IDENTITY)
+    }
+
+    /// Mirror across the main diagonal
+    pub fn transpose() -> Self {
+        Self::new(true, IDENTITY, IDENTITY)
+    }
+
+    /// Mirror across the anti-diagonal
+    pub fn antitranspose() -> Self {
+        Self::new(true, REVERSED, REVERSED)
+    }
+
+    pub fn rotate_clockwise() -> Self {
+        Self::new(true, IDENTITY, REVERSED)
+    }
+
+    pub fn rotate_counterclockwise() -> Self {
+        Self::new(true, REVERSED, IDENTITY)
+    }
+
+    pub fn rotate_180() -> Self {
+        Self::new(false, REVERSED, REVERSED)
+    }
+
+    /// Mirror across the middle row, top and bottom are swapped
+    pub fn reflect_horizontal() -> Self {
+        Self::new(false, REVERSED, IDENTITY)
+    }
+
+    /// Mirror across the middle column, left and right are swapped
+    pub fn reflect_vertical() -> Self {
+        Self::new(false, IDENTITY, REVERSED)
+    }
+
+    /// Swap two rows (1-9) of the same band
+    pub fn swap_rows(a: u8, b: u8) -> Result<Self> {
+        check_same_group(a, b)?;
+        Ok(Self::new(false, swap(a - 1, b - 1), IDENTITY))
+    }
+
+    /// Swap two columns (1-9) of the same stack
+    pub fn swap_cols(a: u8, b: u8) -> Result<Self> {
+        check_same_group(a, b)?;
+        Ok(Self::new(false, IDENTITY, swap(a - 1, b - 1)))
+    }
+
+    /// Swap two bands (1-3), a band being a row of boxes
+    pub fn swap_bands(a: u8, b: u8) -> Result<Self> {
+        check_band(a)?;
+        check_band(b)?;
+        Ok(Self::new(false, swap_groups(a - 1, b - 1), IDENTITY))
+    }
+
+    /// Swap two stacks (1-3), a stack being a column of boxes
+    pub fn swap_stacks(a: u8, b: u8) -> Result<Self> {
+        check_band(a)?;
+        check_band(b)?;
+        Ok(Self::new(false, IDENTITY, swap_groups(a - 1, b - 1)))
+    }
+
+    /// Relabel digits, digit d becomes digits[d - 1]
+    pub fn relabel(digits: [u8; 9]) -> Result<Self> {
+        let mut seen = [false; 9];
+        for d in digits.iter() {
+            check_range(*d)?;
+            seen[(*d - 1) as usize] = true;
+        }
+        if seen.contains(&false) {
+            return Err(Error::new("Relabelling must be a permutation of 1-9"));
+        }
+        let mut t = Self::identity();
+        for (i, d) in digits.iter().enumerate() {
+            t.digits[i] = d - 1;
+        }
+        Ok(t)
+    }
+
+    /// Transform that applies self, then other.
+    pub fn then(&self, other: &Transform) -> Self {
+        let (rows, cols) = if other.transpose {
+            (
+                compose(&self.cols, &other.rows),
+                compose(&self.rows, &other.cols),
+            )
+        } else {
+            (
+                compose(&self.rows, &other.rows),
+                compose(&self.cols, &other.cols),
+            )
+        };
+        Self {
+            transpose: self.transpose != other.transpose,
+            rows,
+            cols,
+            digits: compose(&self.digits, &other.digits),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let (rows, cols) = if self.transpose {
+            (invert(&self.cols), invert(&self.rows))
+        } else {
+            (invert(&self.rows), invert(&self.cols))
+        };
+        Self {
+            transpose: self.transpose,
+            rows,
+            cols,
+            digits: invert(&self.digits),
+        }
+    }
+
+    pub fn map_position(&self, pos: Position) -> Position {
+        let (row, col) = if self.transpose {
+            (pos.col() - 1, pos.row() - 1)
+        } else {
+            (pos.row() - 1, pos.col() - 1)
+        };
+        Position::new(self.cols[col as usize] + 1, self.rows[row as usize] + 1)
+    }
+
+    pub fn map_value(&self, v: CellValue) -> CellValue {
+        let n: usize = v.into();
+        CellValue::new(self.digits[n - 1] + 1)
+    }
+
+    pub fn map_candidates(&self, candidates: &Candidates) -> Candidates {
+        let mut mapped = Candidates::new([false; 9]);
+        for v in candidates.to_vec() {
+            mapped.add(&self.map_value(v));
+        }
+        mapped
+    }
+
+    pub fn map_cell(&self, cell: &Cell) -> Cell {
+        match cell {
+            Cell::Solved(v) => Cell::Solved(self.map_value(*v)),
+            Cell::Unsolved(candidates) => Cell::Unsolved(self.map_candidates(candidates)),
+        }
+    }
+
+    /// Apply to a grid, candidates of unsolved cells are relabelled as well.
+    pub fn apply(&self, grid: &Grid) -> Grid {
+        let mut out = Grid::new();
+        for pos in Position::row_vecs().into_iter().flatten() {
+            out.cells[Grid::index(&self.map_position(pos))] = self.map_cell(&grid.get_cell(pos));
+        }
+        out
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use test_case::test_case;
+
+    use super::*;
+    use crate::fixtures::{PUZZLE, SOLUTION};
+    use crate::solver::backtrack::count_solutions;
+    use crate::solver::strategies::all_strategies;
+    use crate::solver::Solver;
+
+    fn complex() -> Transform {
+        Transform::rotate_clockwise()
+            .then(&Transform::swap_rows(4, 6).unwrap())
+            .then(&Transform::swap_stacks(1, 3).unwrap())
+            .then(&Transform::transpose())
+            .then(&Transform::swap_cols(7, 8).unwrap())
+            .then(&Transform::relabel([2, 3, 1, 5, 4, 9, 8, 7, 6]).unwrap())
+    }
+
+    #[test_case(Transform::identity(); "identity")]
+    #[test_case(Transform::transpose(); "transpose")]
+    #[test_case(Transform::antitranspose(); "antitranspose")]
+    #[test_case(Transform::rotate_clockwise(); "rotate clockwise")]
+    #[test_case(Transform::rotate_counterclockwise(); "rotate counterclockwise")]
+    #[test_case(Transform::rotate_180(); "rotate 180")]
+    #[test_case(Transform::reflect_horizontal(); "reflect horizontal")]
+    #[test_case(Transform::reflect_vertical(); "reflect vertical")]
+    #[test_case(Transform::swap_bands(1, 2).unwrap(); "swap bands")]
+    #[test_case(complex(); "complex")]
+    fn test_transform_preserves_validity(t: Transform) {
+        let grid = Grid::from_str(SOLUTION).unwrap();
+        let transformed = t.apply(&grid);
+        assert_eq!(count_solutions(&transformed, 2), 1);
+        assert_eq!(t.inverse().apply(&transformed), grid);
+        assert_eq!(t.then(&t.inverse()), Transform::identity());
+    }
+
+    #[test]
+    fn test_rotate_clockwise() {
+        let t = Transform::rotate_clockwise();
+        // top-left corner goes to top-right corner
+        assert_eq!(t.map_position(Position::new(1, 1)), Position::new(9, 1));
+        assert_eq!(t.map_position(Position::new(2, 1)), Position::new(9, 2));
+        let full_turn = t.then(&t).then(&t).then(&t);
+        assert_eq!(full_turn, Transform::identity());
+        assert_eq!(t.then(&t), Transform::rotate_180());
+    }
+
+    #[test]
+    fn test_then_applies_in_order() {
+        let grid = Grid::from_str(SOLUTION).unwrap();
+        let a = Transform::rotate_clockwise();
+        let b = Transform::swap_rows(1, 2).unwrap();
+        assert_eq!(a.then(&b).apply(&grid), b.apply(&a.apply(&grid)));
+    }
+
+    #[test]
+    fn test_invalid_swaps() {
+        assert!(Transform::swap_rows(3, 4).is_err());
+        assert!(Transform::swap_cols(0, 1).is_err());
+        assert!(Transform::swap_bands(1, 4).is_err());
+        assert!(Transform::relabel([1, 1, 2, 3, 4, 5, 6, 7, 8]).is_err());
+    }
+
+    #[test]
+    fn test_transform_solve_path() {
+        let solver = Solver::new(all_strategies());
+        let t = complex();
+        let mut grid = Grid::from_str(PUZZLE).unwrap();
+        let mut transformed = t.apply(&grid);
+        while let Some(step) = solver.solve_step(&grid) {
+            step.delta.apply(&mut grid);
+            step.delta.transform(&t).apply(&mut transformed);
+            assert_eq!(transformed, t.apply(&grid));
+        }
+        assert!(transformed.is_solved());
+    }
+}